@@ -0,0 +1,235 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use super::agent_adapter::adapter_for;
+use super::error::AppError;
+use super::terminal::{self, TerminalSession};
+use crate::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentTaskStatus {
+    Running,
+    WaitingForInput,
+    Finished,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentTask {
+    pub task_id: String,
+    pub terminal_id: String,
+    pub worktree: String,
+    pub agent: String,
+    pub prompt: String,
+    pub status: AgentTaskStatus,
+    pub exit_code: Option<u32>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+pub struct AgentTasks {
+    pub tasks: Arc<Mutex<HashMap<String, AgentTask>>>,
+}
+
+fn current_status(
+    terminals: &Mutex<HashMap<String, TerminalSession>>,
+    terminal_id: &str,
+) -> (AgentTaskStatus, Option<u32>) {
+    let terminals = terminals.lock();
+    let Some(session) = terminals.get(terminal_id) else {
+        // Terminal was closed while the agent ran
+        return (AgentTaskStatus::Failed, None);
+    };
+    let info = session.info();
+
+    if !info.alive {
+        let status = match info.exit_code {
+            Some(0) => AgentTaskStatus::Finished,
+            _ => AgentTaskStatus::Failed,
+        };
+        return (status, info.exit_code);
+    }
+
+    if info.needs_attention {
+        (AgentTaskStatus::WaitingForInput, None)
+    } else {
+        (AgentTaskStatus::Running, None)
+    }
+}
+
+/// Polls the task's terminal and emits `agent-task-updated` on every status change.
+fn monitor_task(
+    app: AppHandle,
+    terminals: Arc<Mutex<HashMap<String, TerminalSession>>>,
+    tasks: Arc<Mutex<HashMap<String, AgentTask>>>,
+    task_id: String,
+    terminal_id: String,
+) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+        let (status, exit_code) = current_status(&terminals, &terminal_id);
+        let done = matches!(status, AgentTaskStatus::Finished | AgentTaskStatus::Failed);
+
+        let updated = {
+            let mut tasks = tasks.lock();
+            let Some(task) = tasks.get_mut(&task_id) else {
+                return;
+            };
+            if task.status == status {
+                None
+            } else {
+                let now = Utc::now();
+                task.status = status;
+                task.exit_code = exit_code;
+                task.updated_at = now;
+                if done {
+                    task.finished_at = Some(now);
+                }
+                Some(task.clone())
+            }
+        };
+
+        if let Some(task) = updated {
+            let _ = app.emit("agent-task-updated", task);
+        }
+        if done {
+            return;
+        }
+    });
+}
+
+/// Runs an agent CLI headless in a PTY inside `worktree` and tracks it as a task.
+#[tauri::command]
+pub fn start_agent_task(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    agent_tasks: State<'_, AgentTasks>,
+    worktree: String,
+    agent: String,
+    prompt: String,
+) -> Result<AgentTask, AppError> {
+    if prompt.trim().is_empty() {
+        return Err(AppError::InvalidInput("Prompt cannot be empty".to_string()));
+    }
+    let adapter = adapter_for(&app, &agent)?;
+    let program = adapter.program()?;
+    let args = adapter.args(&prompt, &[]);
+    let terminals = state.terminals.clone();
+
+    let spawned = terminal::spawn_program(
+        app.clone(),
+        state,
+        worktree.clone(),
+        &program,
+        &args,
+        Some(format!(
+            "{}: {}",
+            agent,
+            prompt.lines().next().unwrap_or_default()
+        )),
+        terminal::pty_size(120, 40),
+    )?;
+
+    let now = Utc::now();
+    let task = AgentTask {
+        task_id: spawned.terminal_id.clone(),
+        terminal_id: spawned.terminal_id.clone(),
+        worktree,
+        agent,
+        prompt,
+        status: AgentTaskStatus::Running,
+        exit_code: None,
+        started_at: now,
+        updated_at: now,
+        finished_at: None,
+    };
+    agent_tasks
+        .tasks
+        .lock()
+        .insert(task.task_id.clone(), task.clone());
+    let _ = app.emit("agent-task-updated", task.clone());
+
+    monitor_task(
+        app,
+        terminals,
+        agent_tasks.tasks.clone(),
+        task.task_id.clone(),
+        task.terminal_id.clone(),
+    );
+
+    Ok(task)
+}
+
+#[tauri::command]
+pub fn list_agent_tasks(agent_tasks: State<'_, AgentTasks>) -> Vec<AgentTask> {
+    let mut tasks: Vec<AgentTask> = agent_tasks.tasks.lock().values().cloned().collect();
+    tasks.sort_by_key(|t| t.started_at);
+    tasks
+}
+
+/// Stops a running task by closing its terminal; the task then reports `failed`.
+#[tauri::command]
+pub fn stop_agent_task(
+    state: State<'_, AppState>,
+    agent_tasks: State<'_, AgentTasks>,
+    task_id: String,
+) -> Result<(), AppError> {
+    let terminal_id = agent_tasks
+        .tasks
+        .lock()
+        .get(&task_id)
+        .map(|t| t.terminal_id.clone())
+        .ok_or("Agent task not found")?;
+    terminal::close_terminal(state, terminal_id)
+}
+
+/// Forgets finished and failed tasks and closes their terminal sessions, which
+/// otherwise stay around with their output buffers.
+#[tauri::command]
+pub fn clear_finished_agent_tasks(
+    state: State<'_, AppState>,
+    agent_tasks: State<'_, AgentTasks>,
+) -> Result<(), AppError> {
+    let mut cleared = Vec::new();
+    agent_tasks.tasks.lock().retain(|_, t| {
+        let active = matches!(
+            t.status,
+            AgentTaskStatus::Running | AgentTaskStatus::WaitingForInput
+        );
+        if !active {
+            cleared.push(t.terminal_id.clone());
+        }
+        active
+    });
+    for terminal_id in cleared {
+        terminal::close_terminal(state.clone(), terminal_id)?;
+    }
+    Ok(())
+}
+
+/// Worktrees with a live agent task, used ahead of the process-name heuristics.
+pub fn worktrees_with_running_agents(agent_tasks: &AgentTasks) -> Vec<String> {
+    agent_tasks
+        .tasks
+        .lock()
+        .values()
+        .filter(|t| {
+            matches!(
+                t.status,
+                AgentTaskStatus::Running | AgentTaskStatus::WaitingForInput
+            )
+        })
+        .map(|t| t.worktree.clone())
+        .collect()
+}
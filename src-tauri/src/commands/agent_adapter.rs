@@ -0,0 +1,215 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::cli_tools::find_cli_tool;
+use super::error::AppError;
+use super::runner::ProcessRunner;
+use super::storage::{load_json, save_json};
+
+const CONFIG_FILE: &str = "agents.json";
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const TOOLS_PLACEHOLDER: &str = "{tools}";
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// How an agent CLI is invoked from the app, e.g. for commit messages or headless tasks.
+pub trait AgentAdapter: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Full path of the CLI to run.
+    fn program(&self) -> Result<String, AppError>;
+
+    /// Arguments for a one-shot run of `prompt`, restricted to `allowed_tools`
+    /// when the agent supports it.
+    fn args(&self, prompt: &str, allowed_tools: &[&str]) -> Vec<String>;
+
+    /// Pulls the answer out of the CLI's stdout (e.g. from a JSON envelope).
+    fn extract_output(&self, stdout: &str) -> String;
+
+    fn timeout(&self) -> Duration;
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentConfig {
+    pub name: String,
+    /// Executable name or path; defaults to `name`
+    pub command: Option<String>,
+    /// Argument template, `{prompt}` is replaced with the prompt
+    pub args: Vec<String>,
+    /// Appended when tools are restricted, `{tools}` is replaced with a comma-separated list
+    #[serde(default)]
+    pub allowed_tools_args: Vec<String>,
+    /// Regex whose first capture group is the agent's answer; the whole stdout otherwise
+    pub output_pattern: Option<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct AgentsConfig {
+    agents: Vec<AgentConfig>,
+}
+
+fn builtin(name: &str, args: &[&str], allowed_tools_args: &[&str]) -> AgentConfig {
+    AgentConfig {
+        name: name.to_string(),
+        command: None,
+        args: args.iter().map(|a| a.to_string()).collect(),
+        allowed_tools_args: allowed_tools_args.iter().map(|a| a.to_string()).collect(),
+        output_pattern: None,
+        timeout_secs: DEFAULT_TIMEOUT_SECS,
+    }
+}
+
+fn builtin_agents() -> Vec<AgentConfig> {
+    vec![
+        builtin(
+            "claude",
+            &["-p", "{prompt}"],
+            &["--allowedTools", "{tools}"],
+        ),
+        builtin("opencode", &["run", "{prompt}"], &[]),
+        builtin("aider", &["--message", "{prompt}", "--yes"], &[]),
+        builtin("codex", &["exec", "{prompt}"], &[]),
+        builtin("amp", &["-x", "{prompt}"], &[]),
+        builtin("gemini", &["-p", "{prompt}"], &[]),
+        builtin("goose", &["run", "-t", "{prompt}"], &[]),
+    ]
+}
+
+/// An adapter driven entirely by an `AgentConfig`, built-in or from `agents.json`.
+pub struct ConfiguredAgent {
+    config: AgentConfig,
+    output_re: Option<Regex>,
+}
+
+impl ConfiguredAgent {
+    pub fn new(config: AgentConfig) -> Result<Self, AppError> {
+        let output_re = config
+            .output_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid output pattern for {}: {}", config.name, e))?;
+        Ok(Self { config, output_re })
+    }
+}
+
+impl AgentAdapter for ConfiguredAgent {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn program(&self) -> Result<String, AppError> {
+        let command = self.config.command.as_deref().unwrap_or(&self.config.name);
+        if Path::new(command).is_absolute() {
+            return Ok(command.to_string());
+        }
+        find_cli_tool(command)
+    }
+
+    fn args(&self, prompt: &str, allowed_tools: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = self
+            .config
+            .args
+            .iter()
+            .map(|a| a.replace(PROMPT_PLACEHOLDER, prompt))
+            .collect();
+        if !allowed_tools.is_empty() {
+            let tools = allowed_tools.join(",");
+            args.extend(
+                self.config
+                    .allowed_tools_args
+                    .iter()
+                    .map(|a| a.replace(TOOLS_PLACEHOLDER, &tools)),
+            );
+        }
+        args
+    }
+
+    fn extract_output(&self, stdout: &str) -> String {
+        self.output_re
+            .as_ref()
+            .and_then(|re| re.captures(stdout))
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| stdout.to_string())
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs)
+    }
+}
+
+/// Built-in agents with the user's entries layered on top (same name replaces).
+fn merged_configs(app: &AppHandle) -> Vec<AgentConfig> {
+    let user: AgentsConfig = load_json(app, CONFIG_FILE).unwrap_or_default();
+    let mut agents = builtin_agents();
+    for config in user.agents {
+        match agents.iter_mut().find(|a| a.name == config.name) {
+            Some(existing) => *existing = config,
+            None => agents.push(config),
+        }
+    }
+    agents
+}
+
+/// The adapter for `name`. Unknown agents get the common `<agent> run <prompt>` shape.
+pub fn adapter_for(app: &AppHandle, name: &str) -> Result<Box<dyn AgentAdapter>, AppError> {
+    let config = merged_configs(app)
+        .into_iter()
+        .find(|a| a.name == name)
+        .unwrap_or_else(|| builtin(name, &["run", "{prompt}"], &[]));
+    Ok(Box::new(ConfiguredAgent::new(config)?))
+}
+
+/// Runs a one-shot prompt in `cwd` and returns the extracted output, killing the
+/// agent if it runs past its timeout or `operation_id` is cancelled.
+pub fn run_prompt(
+    adapter: &dyn AgentAdapter,
+    cwd: &str,
+    prompt: &str,
+    allowed_tools: &[&str],
+    operation_id: Option<&str>,
+) -> Result<String, AppError> {
+    let stdout = ProcessRunner::new(adapter.program()?)
+        .args(adapter.args(prompt, allowed_tools))
+        .cwd(cwd)
+        .label(adapter.name())
+        .timeout(adapter.timeout())
+        .operation(operation_id)
+        .run()?;
+    Ok(adapter.extract_output(&stdout))
+}
+
+/// Lists every configured agent, built-in ones included.
+#[tauri::command]
+pub fn list_agent_adapters(app: AppHandle) -> Vec<AgentConfig> {
+    merged_configs(&app)
+}
+
+/// Saves the user's agent entries; they override built-ins with the same name.
+#[tauri::command]
+pub fn set_agent_adapters(app: AppHandle, agents: Vec<AgentConfig>) -> Result<(), AppError> {
+    for config in &agents {
+        if config.name.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Agent name cannot be empty".to_string(),
+            ));
+        }
+        if !config.args.iter().any(|a| a.contains(PROMPT_PLACEHOLDER)) {
+            return Err(AppError::InvalidInput(format!(
+                "Arguments for {} must include {}",
+                config.name, PROMPT_PLACEHOLDER
+            )));
+        }
+        ConfiguredAgent::new(config.clone())?;
+    }
+    save_json(&app, CONFIG_FILE, &AgentsConfig { agents })
+}
@@ -0,0 +1,198 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use super::error::AppError;
+use super::storage::{load_json, save_json};
+use super::transcript::strip_ansi;
+
+const SETTINGS_FILE: &str = "attention.json";
+/// Patterns under this key apply to every terminal running a configured agent
+const ANY_AGENT: &str = "*";
+/// Only the last few lines are checked, so old prompts in the scrollback don't match
+const CHECKED_LINES: usize = 15;
+/// How much of the end of the output callers should pass to `check`
+pub const RECENT_OUTPUT_BYTES: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttentionSettings {
+    /// How long output has to be quiet before the prompt patterns are checked
+    pub idle_ms: u64,
+    /// Show a desktop notification in addition to the event
+    pub notify: bool,
+    /// Regexes per agent command name (`claude`, `aider`, ...), plus `*` for all of them.
+    /// Terminals running anything else, like a plain shell, are never checked.
+    pub patterns: HashMap<String, Vec<String>>,
+}
+
+impl Default for AttentionSettings {
+    fn default() -> Self {
+        let patterns = [
+            (
+                ANY_AGENT,
+                vec![
+                    r"(?i)\(y/n\)",
+                    r"(?i)\[y/n\]",
+                    r"(?i)press enter to continue",
+                ],
+            ),
+            ("claude", vec![r"Do you want to", r"(?i)esc to cancel"]),
+            ("aider", vec![r"\(Y\)es/\(N\)o", r"\[Yes\]:\s*$"]),
+            (
+                "opencode",
+                vec![r"(?i)allow once", r"(?i)permission required"],
+            ),
+            (
+                "codex",
+                vec![
+                    r"(?i)allow command\?",
+                    r"Would you like to (run the following command|make the following edits)\?",
+                ],
+            ),
+        ]
+        .into_iter()
+        .map(|(agent, patterns)| {
+            (
+                agent.to_string(),
+                patterns.into_iter().map(String::from).collect(),
+            )
+        })
+        .collect();
+
+        Self {
+            idle_ms: 1500,
+            notify: true,
+            patterns,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentNeedsAttention {
+    pub terminal_id: String,
+    pub worktree: Option<String>,
+    pub agent: Option<String>,
+    /// The output line that matched a prompt pattern
+    pub line: String,
+}
+
+pub fn load_settings(app: &AppHandle) -> AttentionSettings {
+    load_json(app, SETTINGS_FILE).unwrap_or_default()
+}
+
+/// `/usr/local/bin/claude --resume` -> `claude`
+pub fn agent_name(command: &str) -> Option<String> {
+    let program = command.split_whitespace().next()?;
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    Some(name.to_lowercase())
+}
+
+/// Watches a terminal's output for an agent prompt followed by silence.
+pub struct AttentionDetector {
+    idle: Duration,
+    notify: bool,
+    patterns: HashMap<String, Vec<Regex>>,
+    last_output: Option<Instant>,
+    raised: bool,
+}
+
+impl AttentionDetector {
+    pub fn new(settings: &AttentionSettings) -> Self {
+        let patterns = settings
+            .patterns
+            .iter()
+            .map(|(agent, patterns)| {
+                let compiled = patterns.iter().filter_map(|p| Regex::new(p).ok()).collect();
+                (agent.to_lowercase(), compiled)
+            })
+            .collect();
+
+        Self {
+            idle: Duration::from_millis(settings.idle_ms),
+            notify: settings.notify,
+            patterns,
+            last_output: None,
+            raised: false,
+        }
+    }
+
+    pub fn output_received(&mut self) {
+        self.last_output = Some(Instant::now());
+        self.raised = false;
+    }
+
+    /// Time left until the output counts as idle, if a check is still due.
+    pub fn pending_check(&self) -> Option<Duration> {
+        if self.raised {
+            return None;
+        }
+        let last_output = self.last_output?;
+        Some(self.idle.saturating_sub(last_output.elapsed()))
+    }
+
+    /// Checks the end of the output once it has been idle long enough. Returns the
+    /// matching line the first time a prompt is found after new output.
+    pub fn check(&mut self, agent: Option<&str>, recent_output: &str) -> Option<String> {
+        if self.pending_check()? > Duration::ZERO {
+            return None;
+        }
+        // Either way, don't check again until there's new output
+        self.last_output = None;
+
+        // Generic prompts like "(y/n)" only count in agent terminals, not in shells
+        let agent = agent?;
+        let patterns: Vec<&Regex> = self
+            .patterns
+            .get(ANY_AGENT)
+            .into_iter()
+            .flatten()
+            .chain(self.patterns.get(agent).into_iter().flatten())
+            .collect();
+
+        let text = strip_ansi(recent_output);
+        let line = text
+            .lines()
+            .rev()
+            .filter(|l| !l.trim().is_empty())
+            .take(CHECKED_LINES)
+            .find(|line| patterns.iter().any(|re| re.is_match(line)))?
+            .trim()
+            .to_string();
+
+        self.raised = true;
+        Some(line)
+    }
+
+    pub fn raise(&self, app: &AppHandle, event: AgentNeedsAttention) {
+        if self.notify {
+            let title = match &event.agent {
+                Some(agent) => format!("{} needs your input", agent),
+                None => "A terminal needs your input".to_string(),
+            };
+            let body = match &event.worktree {
+                Some(worktree) => format!("{}\n{}", worktree, event.line),
+                None => event.line.clone(),
+            };
+            let _ = app.notification().builder().title(title).body(body).show();
+        }
+        let _ = app.emit("agent-needs-attention", event);
+    }
+}
+
+#[tauri::command]
+pub fn get_attention_settings(app: AppHandle) -> AttentionSettings {
+    load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_attention_settings(app: AppHandle, settings: AttentionSettings) -> Result<(), AppError> {
+    for (agent, patterns) in &settings.patterns {
+        for pattern in patterns {
+            Regex::new(pattern).map_err(|e| format!("Invalid pattern for {}: {}", agent, e))?;
+        }
+    }
+    save_json(&app, SETTINGS_FILE, &settings)
+}
@@ -1,8 +1,13 @@
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
 use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::error::AppError;
+use super::runner::ProcessRunner;
+
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
 
 static CLI_TOOL_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
 
@@ -14,7 +19,7 @@ const COMMON_PATHS: &[&str] = &[
     "/opt/local/bin",
 ];
 
-pub fn find_cli_tool(name: &str) -> Result<String, String> {
+pub fn find_cli_tool(name: &str) -> Result<String, AppError> {
     let cache = CLI_TOOL_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
 
     {
@@ -32,9 +37,13 @@ pub fn find_cli_tool(name: &str) -> Result<String, String> {
         }
     }
 
-    if let Ok(output) = Command::new("which").arg(name).output() {
-        if output.status.success() {
-            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if let Ok(output) = ProcessRunner::new("which")
+        .arg(name)
+        .timeout(LOOKUP_TIMEOUT)
+        .output()
+    {
+        if output.success() {
+            let path = output.stdout.trim().to_string();
             if !path.is_empty() && Path::new(&path).exists() {
                 cache.lock().insert(name.to_string(), path.clone());
                 return Ok(path);
@@ -47,14 +56,9 @@ pub fn find_cli_tool(name: &str) -> Result<String, String> {
         return Ok(path);
     }
 
-    Err(format!(
-        "{} CLI not found. Please ensure {} is installed.\n\
-         Expected locations:\n\
-         - /opt/homebrew/bin/{} (Homebrew on Apple Silicon)\n\
-         - /usr/local/bin/{} (Homebrew on Intel)\n\
-         Install with: brew install {}",
-        name, name, name, name, name
-    ))
+    Err(AppError::ToolNotFound {
+        name: name.to_string(),
+    })
 }
 
 fn is_executable(path: &str) -> bool {
@@ -81,13 +85,15 @@ fn try_shell_which(name: &str) -> Option<String> {
             continue;
         }
 
-        let result = Command::new(shell)
+        // Login shells source the user's profile, which can hang on a broken setup
+        let result = ProcessRunner::new(shell)
             .args(["-l", "-c", &format!("which {}", name)])
+            .timeout(LOOKUP_TIMEOUT)
             .output();
 
         if let Ok(output) = result {
-            if output.status.success() {
-                let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if output.success() {
+                let path = output.stdout.trim().to_string();
                 if !path.is_empty() && Path::new(&path).exists() {
                     return Some(path);
                 }
@@ -0,0 +1,243 @@
+use git2::build::RepoBuilder;
+use git2::{Config, Cred, CredentialType, FetchOptions, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use super::error::AppError;
+use super::git::{self, WorktreeInfo};
+use super::naming::expand_home;
+use super::registry::{register_path, RegisteredRepo};
+use super::runner::{Operation, OutputStream, ProcessRunner};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CloneProgress {
+    pub destination: String,
+    pub received_objects: usize,
+    pub total_objects: usize,
+    pub indexed_deltas: usize,
+    pub total_deltas: usize,
+    pub received_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloneResult {
+    pub repo: RegisteredRepo,
+    pub worktree: Option<WorktreeInfo>,
+}
+
+/// `https://host/org/name.git` and `git@host:org/name.git` both become `name`.
+fn directory_name_from_url(url: &str) -> Option<String> {
+    let trimmed = url.trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn emit_progress(app: &AppHandle, progress: CloneProgress) {
+    let _ = app.emit("clone-progress", progress);
+}
+
+fn clone_with_libgit2(
+    app: &AppHandle,
+    url: &str,
+    destination: &Path,
+    depth: Option<u32>,
+) -> Result<(), AppError> {
+    let dest_str = destination.to_string_lossy().to_string();
+    let config = Config::open_default()?;
+    let mut ssh_agent_tried = false;
+    let mut helper_tried = false;
+    let mut last_emit = std::time::Instant::now();
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        // libgit2 keeps asking until a credential works, so each kind is tried once
+        if allowed.contains(CredentialType::SSH_KEY) && !ssh_agent_tried {
+            ssh_agent_tried = true;
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !helper_tried {
+            helper_tried = true;
+            return Cred::credential_helper(&config, url, username);
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(git2::Error::from_str("No usable credentials for this remote"))
+    });
+    callbacks.transfer_progress(|stats| {
+        let done = stats.received_objects() == stats.total_objects()
+            && stats.indexed_deltas() == stats.total_deltas();
+        if done || last_emit.elapsed().as_millis() >= 100 {
+            last_emit = std::time::Instant::now();
+            emit_progress(
+                app,
+                CloneProgress {
+                    destination: dest_str.clone(),
+                    received_objects: stats.received_objects(),
+                    total_objects: stats.total_objects(),
+                    indexed_deltas: stats.indexed_deltas(),
+                    total_deltas: stats.total_deltas(),
+                    received_bytes: stats.received_bytes(),
+                },
+            );
+        }
+        true
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    if let Some(depth) = depth {
+        fetch_options.depth(depth as i32);
+    }
+
+    RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, destination)?;
+    Ok(())
+}
+
+/// Parses `Receiving objects:  45% (450/1000), 1.2 MiB | ...` style lines.
+fn parse_cli_progress(line: &str) -> Option<(&str, usize, usize)> {
+    let (stage, rest) = line.split_once(':')?;
+    let counts = rest.split_once('(')?.1.split_once(')')?.0;
+    let (done, total) = counts.split_once('/')?;
+    Some((stage.trim(), done.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+/// libgit2 has no partial clone support, so filtered clones go through the git CLI.
+fn clone_with_cli(
+    app: &AppHandle,
+    url: &str,
+    destination: &Path,
+    depth: Option<u32>,
+    filter: &str,
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    let dest_str = destination.to_string_lossy().to_string();
+    let mut args = vec![
+        "clone".to_string(),
+        "--progress".to_string(),
+        format!("--filter={}", filter),
+    ];
+    if let Some(depth) = depth {
+        args.push(format!("--depth={}", depth));
+    }
+    args.push(url.to_string());
+    args.push(dest_str.clone());
+
+    let mut output = String::new();
+    let mut progress = CloneProgress {
+        destination: dest_str,
+        received_objects: 0,
+        total_objects: 0,
+        indexed_deltas: 0,
+        total_deltas: 0,
+        received_bytes: 0,
+    };
+
+    let result = ProcessRunner::new("git")
+        .args(args)
+        .no_timeout()
+        .operation(operation_id)
+        .on_line(|stream, line| {
+            if stream != OutputStream::Stderr {
+                return;
+            }
+            match parse_cli_progress(line) {
+                Some((stage, done, total)) if stage.ends_with("Receiving objects") => {
+                    progress.received_objects = done;
+                    progress.total_objects = total;
+                    emit_progress(app, progress.clone());
+                }
+                Some((stage, done, total)) if stage.ends_with("Resolving deltas") => {
+                    progress.indexed_deltas = done;
+                    progress.total_deltas = total;
+                    emit_progress(app, progress.clone());
+                }
+                Some(_) => {}
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        })
+        .output()?;
+
+    if !result.success() {
+        return Err(AppError::CommandFailed {
+            command: "git clone".to_string(),
+            stderr: output.trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Clones `url` into `parent_dir/<directory_name>` and adds it to the repository
+/// registry. Progress is reported through `clone-progress` events. Only filtered
+/// clones, which run the git CLI, can be cancelled through `operation_id`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_repository(
+    app: AppHandle,
+    url: String,
+    parent_dir: String,
+    directory_name: Option<String>,
+    depth: Option<u32>,
+    filter: Option<String>,
+    create_worktree: bool,
+    operation_id: Option<String>,
+) -> Result<CloneResult, AppError> {
+    let url = url.trim().to_string();
+    let name = match directory_name.filter(|n| !n.trim().is_empty()) {
+        Some(name) => name.trim().to_string(),
+        None => directory_name_from_url(&url)
+            .ok_or_else(|| format!("Cannot derive a directory name from {}", url))?,
+    };
+    let destination: PathBuf = expand_home(&parent_dir).join(&name);
+    if destination.exists() {
+        return Err(AppError::InvalidInput(format!(
+            "Destination already exists: {}",
+            destination.display()
+        )));
+    }
+    if depth == Some(0) {
+        return Err(AppError::InvalidInput("Clone depth must be at least 1".to_string()));
+    }
+    let _operation = Operation::begin(operation_id.as_deref());
+
+    tokio::task::spawn_blocking(move || {
+        let cloned = match filter.as_deref().filter(|f| !f.is_empty()) {
+            Some(filter) => clone_with_cli(
+                &app,
+                &url,
+                &destination,
+                depth,
+                filter,
+                operation_id.as_deref(),
+            ),
+            None => clone_with_libgit2(&app, &url, &destination, depth),
+        };
+        if let Err(e) = cloned {
+            let _ = std::fs::remove_dir_all(&destination);
+            return Err(e);
+        }
+
+        let repo = register_path(&app, &destination.to_string_lossy())?;
+        let worktree = if create_worktree {
+            Some(git::create_worktree_auto_blocking(
+                &app,
+                repo.path.clone(),
+                None,
+                None,
+                operation_id.as_deref(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(CloneResult { repo, worktree })
+    })
+    .await?
+}
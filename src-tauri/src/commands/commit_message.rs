@@ -0,0 +1,188 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::agent_adapter::{adapter_for, run_prompt};
+use super::error::AppError;
+use super::git::run_git;
+
+/// Larger diffs are cut off; the stat summary still covers every file
+const MAX_DIFF_BYTES: usize = 24_000;
+const MAX_SUBJECT_LEN: usize = 72;
+const STYLE_SAMPLE_COMMITS: &str = "20";
+const CONVENTIONAL_TYPES: &str = "feat|fix|docs|style|refactor|perf|test|build|ci|chore|revert";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitMessage {
+    pub subject: String,
+    pub body: Option<String>,
+    /// Subject and body joined the way `git commit` expects
+    pub message: String,
+    /// Problems the user may want to fix before committing, e.g. a long subject
+    pub warnings: Vec<String>,
+}
+
+/// Cuts `text` to at most `max_bytes`, on a line boundary when possible.
+pub fn truncate_text(text: &str, max_bytes: usize) -> (&str, bool) {
+    if text.len() <= max_bytes {
+        return (text, false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = text[..end].rfind('\n').unwrap_or(end);
+    (&text[..end], true)
+}
+
+/// The staged diff as prompt context: a `--stat` summary plus the (possibly truncated) patch.
+fn staged_diff(worktree_path: &str) -> Result<String, AppError> {
+    let stat = run_git(worktree_path, &["diff", "--cached", "--stat"])?;
+    if stat.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "No staged changes to describe".to_string(),
+        ));
+    }
+    let patch = run_git(
+        worktree_path,
+        &["diff", "--cached", "--no-color", "--no-ext-diff"],
+    )?;
+
+    let (patch, truncated) = truncate_text(&patch, MAX_DIFF_BYTES);
+    let mut diff = format!("{}\n{}", stat.trim_end(), patch);
+    if truncated {
+        diff.push_str("\n[diff truncated]");
+    }
+    Ok(diff)
+}
+
+/// Recent commit subjects, so the agent can match the repository's existing style.
+fn recent_subjects(worktree_path: &str) -> Vec<String> {
+    // Fails on a repository without commits, which simply has no style yet
+    run_git(
+        worktree_path,
+        &[
+            "log",
+            "-n",
+            STYLE_SAMPLE_COMMITS,
+            "--no-merges",
+            "--format=%s",
+        ],
+    )
+    .map(|log| {
+        log.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(String::from)
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn build_prompt(diff: &str, style: &[String], conventional: bool) -> String {
+    let mut prompt = String::from(
+        "Write a git commit message for the staged changes below.\n\
+         The subject is one line in the imperative mood, at most 72 characters, without a trailing period.\n\
+         The body is optional; when present it explains what changed and why, wrapped at 72 characters.\n",
+    );
+    if conventional {
+        prompt.push_str(&format!(
+            "Use the Conventional Commits format for the subject: <type>(<optional scope>): <description>, \
+             where type is one of {}.\n",
+            CONVENTIONAL_TYPES.replace('|', ", ")
+        ));
+    }
+    if !style.is_empty() {
+        prompt
+            .push_str("\nMatch the style of these recent commit subjects from this repository:\n");
+        for subject in style {
+            prompt.push_str(&format!("- {}\n", subject));
+        }
+    }
+    prompt.push_str(
+        "\nReturn ONLY the message as <subject>...</subject> followed by an optional <body>...</body>. No other text.\n\
+         \nStaged changes:\n",
+    );
+    prompt.push_str(diff);
+    prompt
+}
+
+pub fn tag_content(text: &str, tag: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?s)<{tag}>\s*(.*?)\s*</{tag}>")).ok()?;
+    let content = re.captures(text)?.get(1)?.as_str().trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// Reads `<subject>`/`<body>` tags, falling back to treating the first line as the subject.
+fn parse_response(output: &str) -> Result<(String, Option<String>), AppError> {
+    if let Some(subject) = tag_content(output, "subject") {
+        return Ok((subject, tag_content(output, "body")));
+    }
+
+    let text = output.trim().trim_matches('`').trim();
+    let mut lines = text.lines();
+    let subject = lines
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l.trim().to_string())
+        .ok_or("Could not extract commit message from AI response")?;
+    let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    Ok((subject, (!body.is_empty()).then_some(body)))
+}
+
+fn check_subject(subject: &str, conventional: bool) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let len = subject.chars().count();
+    if len > MAX_SUBJECT_LEN {
+        warnings.push(format!(
+            "Subject is {} characters long; keep it to {} or fewer",
+            len, MAX_SUBJECT_LEN
+        ));
+    }
+    if conventional {
+        let re = Regex::new(&format!(r"^({})(\([^)]+\))?!?: \S", CONVENTIONAL_TYPES))
+            .expect("valid regex");
+        if !re.is_match(subject) {
+            warnings.push("Subject doesn't follow the Conventional Commits format".to_string());
+        }
+    }
+    warnings
+}
+
+/// Generates a commit message for the staged changes by handing their diff to `agent`.
+#[tauri::command]
+pub async fn generate_commit_message(
+    app: AppHandle,
+    worktree_path: String,
+    agent: String,
+    conventional: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<CommitMessage, AppError> {
+    let adapter = adapter_for(&app, &agent)?;
+    let conventional = conventional.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        let diff = staged_diff(&worktree_path)?;
+        let style = recent_subjects(&worktree_path);
+        let prompt = build_prompt(&diff, &style, conventional);
+
+        let output = run_prompt(
+            adapter.as_ref(),
+            &worktree_path,
+            &prompt,
+            &[],
+            operation_id.as_deref(),
+        )?;
+        let (subject, body) = parse_response(&output)?;
+
+        let message = match &body {
+            Some(body) => format!("{}\n\n{}", subject, body),
+            None => subject.clone(),
+        };
+        Ok(CommitMessage {
+            warnings: check_subject(&subject, conventional),
+            subject,
+            body,
+            message,
+        })
+    })
+    .await?
+}
@@ -0,0 +1,235 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+
+use super::runner::RunError;
+
+/// Error returned by every command. Serializes as `{ code, message, details }` so the
+/// frontend can branch on `code` instead of matching message text.
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// The path isn't a git repository, or no longer exists
+    RepoNotFound {
+        path: String,
+    },
+    /// Neither origin/main nor origin/master exists
+    BaseBranchMissing,
+    GhNotInstalled,
+    GhNotAuthenticated,
+    MergeConflict {
+        files: Vec<String>,
+    },
+    /// An external command ran past its timeout
+    Timeout {
+        command: String,
+        seconds: u64,
+    },
+    Cancelled {
+        command: String,
+    },
+    /// A CLI tool (agent, cubic, ...) isn't installed
+    ToolNotFound {
+        name: String,
+    },
+    /// An external command exited unsuccessfully
+    CommandFailed {
+        command: String,
+        stderr: String,
+    },
+    /// A forge API request was rejected; `status` is missing for GraphQL errors
+    Api {
+        status: Option<u16>,
+        message: String,
+    },
+    /// Bad arguments from the caller, e.g. an invalid branch name
+    InvalidInput(String),
+    NotFound(String),
+    Git(String),
+    Io(String),
+    Other(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::RepoNotFound { .. } => "repo_not_found",
+            AppError::BaseBranchMissing => "base_branch_missing",
+            AppError::GhNotInstalled => "gh_not_installed",
+            AppError::GhNotAuthenticated => "gh_not_authenticated",
+            AppError::MergeConflict { .. } => "merge_conflict",
+            AppError::Timeout { .. } => "timeout",
+            AppError::Cancelled { .. } => "cancelled",
+            AppError::ToolNotFound { .. } => "tool_not_found",
+            AppError::CommandFailed { .. } => "command_failed",
+            AppError::Api { .. } => "api",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::NotFound(_) => "not_found",
+            AppError::Git(_) => "git",
+            AppError::Io(_) => "io",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            AppError::RepoNotFound { path } => Some(json!({ "path": path })),
+            AppError::MergeConflict { files } => Some(json!({ "files": files })),
+            AppError::Timeout { command, seconds } => {
+                Some(json!({ "command": command, "seconds": seconds }))
+            }
+            AppError::Cancelled { command } => Some(json!({ "command": command })),
+            AppError::ToolNotFound { name } => Some(json!({ "name": name })),
+            AppError::CommandFailed { command, stderr } => {
+                Some(json!({ "command": command, "stderr": stderr }))
+            }
+            AppError::Api { status, .. } => Some(json!({ "status": status })),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::RepoNotFound { path } => write!(f, "Not a git repository: {}", path),
+            AppError::BaseBranchMissing => write!(f, "Cannot find origin/main or origin/master"),
+            AppError::GhNotInstalled => write!(
+                f,
+                "GitHub CLI (gh) not found. Install it with: brew install gh"
+            ),
+            AppError::GhNotAuthenticated => write!(f, "Not logged in to GitHub"),
+            AppError::MergeConflict { files } if files.is_empty() => write!(f, "Merge conflict"),
+            AppError::MergeConflict { files } => {
+                write!(f, "Merge conflict in {}", files.join(", "))
+            }
+            AppError::Timeout { command, seconds } => {
+                write!(f, "{} timed out after {}s", command, seconds)
+            }
+            AppError::Cancelled { command } => write!(f, "{} was cancelled", command),
+            AppError::ToolNotFound { name } => write!(
+                f,
+                "{} CLI not found. Please ensure {} is installed.\n\
+                 Expected locations:\n\
+                 - /opt/homebrew/bin/{} (Homebrew on Apple Silicon)\n\
+                 - /usr/local/bin/{} (Homebrew on Intel)\n\
+                 Install with: brew install {}",
+                name, name, name, name, name
+            ),
+            AppError::CommandFailed { command, stderr } => {
+                write!(f, "{} failed: {}", command, stderr.trim())
+            }
+            AppError::Api {
+                status: Some(status),
+                message,
+            } => write!(f, "API request failed ({}): {}", status, message),
+            AppError::Api {
+                status: None,
+                message,
+            } => write!(f, "API request failed: {}", message),
+            AppError::InvalidInput(message)
+            | AppError::NotFound(message)
+            | AppError::Git(message)
+            | AppError::Io(message)
+            | AppError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl From<git2::Error> for AppError {
+    fn from(error: git2::Error) -> Self {
+        match error.code() {
+            git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => {
+                AppError::MergeConflict { files: Vec::new() }
+            }
+            _ => AppError::Git(error.message().to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::Other(format!("Invalid JSON: {}", error))
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        AppError::Other(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        AppError::Api {
+            status: error.status().map(|s| s.as_u16()),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// `CONFLICT (content): Merge conflict in src/main.rs` -> `src/main.rs`
+fn conflicted_files(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|l| l.starts_with("CONFLICT"))
+        .filter_map(|l| {
+            l.rsplit_once(" in ")
+                .map(|(_, file)| file.trim().to_string())
+        })
+        .collect()
+}
+
+impl From<RunError> for AppError {
+    fn from(error: RunError) -> Self {
+        match error {
+            RunError::Io { command, message } => {
+                AppError::Io(format!("Failed to run {}: {}", command, message))
+            }
+            RunError::Failed {
+                command,
+                stdout,
+                stderr,
+                ..
+            } => {
+                // git reports conflicts on stdout for merges and on stderr for rebases
+                if stdout.contains("CONFLICT") || stderr.contains("CONFLICT") {
+                    let files = conflicted_files(&format!("{}\n{}", stdout, stderr));
+                    return AppError::MergeConflict { files };
+                }
+                AppError::CommandFailed { command, stderr }
+            }
+            RunError::TimedOut { command, seconds } => AppError::Timeout { command, seconds },
+            RunError::Cancelled { command } => AppError::Cancelled { command },
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use reqwest::blocking::{Client, Response};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::error::AppError;
+use super::git::{open_repo, resolve_base_branch};
+use super::github::{
+    backend_for, CreatePRResult, NewPullRequest, PRChecksResult, PRDetailedInfo, PRHead, PRStatus,
+};
+use super::github_api::DEFAULT_HOST;
+use super::gitlab::GitLabApi;
+use super::runner::Operation;
+use super::storage::{load_json, save_json};
+
+const SETTINGS_FILE: &str = "forges.json";
+pub const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Everything the app asks a code forge about pull requests (merge requests on
+/// GitLab). Calls block, so commands run them off the async runtime.
+pub trait Forge: Send + Sync {
+    /// Login of the user authenticated on `host`.
+    fn current_user(&self, host: &str) -> Result<String, AppError>;
+
+    /// The most recent PR whose head is `branch`, open or not.
+    fn pr_for_branch(&self, repo_path: &str, branch: &str) -> Result<Option<PRStatus>, AppError>;
+
+    /// PRs for several branches at once; branches without a PR are left out.
+    fn prs_for_branches(
+        &self,
+        repo_path: &str,
+        branches: &[String],
+    ) -> Result<Vec<PRStatus>, AppError> {
+        Ok(branches
+            .iter()
+            .filter_map(|branch| self.pr_for_branch(repo_path, branch).ok().flatten())
+            .collect())
+    }
+
+    fn pr_status(&self, repo_path: &str, number: u64) -> Result<PRStatus, AppError>;
+
+    fn pr_head(&self, repo_path: &str, number: u64) -> Result<PRHead, AppError>;
+
+    /// Ref the forge publishes a PR's head commit under, for fetching PRs from forks.
+    fn head_ref(&self, number: u64) -> String {
+        format!("pull/{}/head", number)
+    }
+
+    /// `owner/name` of the repository, if the forge knows it.
+    fn repo_name(&self, repo_path: &str) -> Result<Option<String>, AppError>;
+
+    fn pr_checks(&self, repo_path: &str, number: u64) -> Result<PRChecksResult, AppError>;
+
+    /// Merge state plus the full comment timeline.
+    fn pr_details(&self, repo_path: &str, number: u64) -> Result<PRDetailedInfo, AppError>;
+
+    /// Opens a PR from the branch checked out in `repo_path`. The branch has to be
+    /// pushed already; no backend pushes it, and the forge rejects unknown heads.
+    /// A cancelled `operation_id` stops the call before the PR is requested; the
+    /// HTTP backends can't abort a request already sent.
+    fn create_pr(
+        &self,
+        repo_path: &str,
+        pr: &NewPullRequest,
+        operation_id: Option<&str>,
+    ) -> Result<CreatePRResult, AppError>;
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ForgeSettings {
+    /// Self-hosted GitLab hosts whose name doesn't contain "gitlab"
+    pub gitlab_hosts: Vec<String>,
+}
+
+fn load_settings(app: &AppHandle) -> ForgeSettings {
+    load_json(app, SETTINGS_FILE).unwrap_or_default()
+}
+
+/// A repository on a forge, as named by its `origin` remote.
+#[derive(Debug, Clone)]
+pub struct RemoteRepo {
+    pub host: String,
+    /// Owner, or the full group path on forges with nested groups
+    pub owner: String,
+    pub name: String,
+}
+
+/// Parses `git@host:owner/name.git`, `ssh://git@host:22/owner/name` and
+/// `https://host/owner/name` style remote URLs.
+pub fn parse_remote_url(url: &str) -> Option<RemoteRepo> {
+    let url = url.trim();
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            (host.split(':').next()?, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.rsplit_once('/')?;
+    if host.is_empty() || owner.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some(RemoteRepo {
+        host: host.to_lowercase(),
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+pub fn remote_repo(repo_path: &str) -> Result<RemoteRepo, AppError> {
+    let repo = open_repo(repo_path)?;
+    let remote = repo
+        .find_remote("origin")
+        .map_err(|_| AppError::NotFound("Repository has no origin remote".to_string()))?;
+    let url = remote.url().unwrap_or_default();
+    parse_remote_url(url)
+        .ok_or_else(|| AppError::InvalidInput(format!("Cannot parse remote URL: {}", url)))
+}
+
+fn forge_kind(app: &AppHandle, host: &str) -> ForgeKind {
+    let configured = load_settings(app)
+        .gitlab_hosts
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case(host));
+    if configured || host.contains("gitlab") {
+        ForgeKind::GitLab
+    } else {
+        ForgeKind::GitHub
+    }
+}
+
+/// The forge hosting `repo_path`'s origin. Anything that isn't recognizably GitLab
+/// is treated as GitHub, which covers GitHub Enterprise hosts.
+pub fn forge_for(app: &AppHandle, repo_path: &str) -> Box<dyn Forge> {
+    match remote_repo(repo_path) {
+        Ok(remote) => match forge_kind(app, &remote.host) {
+            ForgeKind::GitLab => Box::new(GitLabApi),
+            ForgeKind::GitHub => backend_for(app, &remote.host),
+        },
+        // Without a parsable remote gh gets to report the problem, as it always did
+        Err(_) => backend_for(app, DEFAULT_HOST),
+    }
+}
+
+/// Runs a blocking forge call for `repo_path` on the blocking thread pool.
+async fn with_forge<T, F>(app: &AppHandle, repo_path: String, call: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Forge, &str) -> Result<T, AppError> + Send + 'static,
+{
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || {
+        // Picking the forge can mean a keychain lookup, so it happens off the runtime too
+        let forge = forge_for(&app, &repo_path);
+        call(forge.as_ref(), &repo_path)
+    })
+    .await?
+}
+
+/// Shared HTTP client for the forge APIs.
+pub fn http_client() -> Result<&'static Client, AppError> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = Client::builder()
+        .user_agent("autopilot")
+        .timeout(API_TIMEOUT)
+        .build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Maps a request that got no response, turning timeouts into `Timeout`.
+pub fn request_error(host: &str, error: reqwest::Error) -> AppError {
+    if error.is_timeout() {
+        return AppError::Timeout {
+            command: format!("API request to {}", host),
+            seconds: API_TIMEOUT.as_secs(),
+        };
+    }
+    error.into()
+}
+
+/// The error for an unsuccessful response, with the API's own message when it sent one.
+pub fn response_error(response: Response) -> AppError {
+    let status = response.status();
+    let message = response
+        .json::<Value>()
+        .ok()
+        .and_then(|body| match &body["message"] {
+            Value::String(message) => Some(message.clone()),
+            Value::Null => body["error"].as_str().map(String::from),
+            // GitLab reports validation errors as an object of field -> messages
+            other => Some(other.to_string()),
+        })
+        .unwrap_or_else(|| status.to_string());
+    AppError::Api {
+        status: Some(status.as_u16()),
+        message,
+    }
+}
+
+/// The branch checked out in `repo_path`, which a new PR is opened from.
+pub fn current_branch(repo_path: &str) -> Result<String, AppError> {
+    let repo = open_repo(repo_path)?;
+    let head = repo.head()?;
+    head.shorthand()
+        .filter(|_| head.is_branch())
+        .map(String::from)
+        .ok_or_else(|| AppError::InvalidInput("HEAD is not on a branch".to_string()))
+}
+
+/// The URL in `Link: <...>; rel="next"`, if there is another page.
+pub fn next_page(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get("link")?.to_str().ok()?;
+    let next = link.split(',').find(|part| part.contains("rel=\"next\""))?;
+    let start = next.find('<')? + 1;
+    let end = next.find('>')?;
+    Some(next[start..end].to_string())
+}
+
+/// Reads `key` from the `host:` section of a gh/glab style YAML config, e.g.
+/// `oauth_token` from gh's hosts.yml or `token` from glab's config.yml.
+pub fn host_config_value(contents: &str, host: &str, key: &str) -> Option<String> {
+    let header = format!("{}:", host);
+    let key = format!("{}:", key);
+    let mut host_indent = None;
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if host_indent.is_some_and(|host_indent| indent <= host_indent) {
+            host_indent = None;
+        }
+        if trimmed.trim_end() == header {
+            host_indent = Some(indent);
+            continue;
+        }
+        if let Some(value) = trimmed.strip_prefix(&key).filter(|_| host_indent.is_some()) {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            if !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepoWithBranches {
+    pub repo_path: String,
+    pub branches: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoPRStatuses {
+    pub repo_path: String,
+    pub statuses: Vec<PRStatus>,
+    /// Why the statuses couldn't be fetched; `statuses` is empty then
+    pub error: Option<AppError>,
+}
+
+#[tauri::command]
+pub async fn get_pr_for_branch(
+    app: AppHandle,
+    repo_path: String,
+    branch: String,
+) -> Result<Option<PRStatus>, AppError> {
+    with_forge(&app, repo_path, move |forge, repo_path| {
+        forge.pr_for_branch(repo_path, &branch)
+    })
+    .await
+}
+
+/// PR statuses for many branches across repositories. The GitHub HTTP backend needs
+/// a single request per repository; a repository that fails is reported with its error.
+#[tauri::command]
+pub async fn get_all_prs_for_repos(
+    app: AppHandle,
+    repos: Vec<RepoWithBranches>,
+) -> Result<Vec<RepoPRStatuses>, AppError> {
+    let mut results = Vec::new();
+
+    for repo in repos {
+        let branches = repo.branches;
+        let fetched = with_forge(&app, repo.repo_path.clone(), move |forge, repo_path| {
+            forge.prs_for_branches(repo_path, &branches)
+        })
+        .await;
+        let (statuses, error) = match fetched {
+            Ok(statuses) => (statuses, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+
+        results.push(RepoPRStatuses {
+            repo_path: repo.repo_path,
+            statuses,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn get_pr_status(
+    app: AppHandle,
+    repo_path: String,
+    pr_number: u64,
+) -> Result<PRStatus, AppError> {
+    with_forge(&app, repo_path, move |forge, repo_path| {
+        forge.pr_status(repo_path, pr_number)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_repo_from_remote(
+    app: AppHandle,
+    repo_path: String,
+) -> Result<Option<String>, AppError> {
+    with_forge(&app, repo_path, |forge, repo_path| {
+        forge.repo_name(repo_path)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_pr_checks(
+    app: AppHandle,
+    repo_path: String,
+    pr_number: u64,
+) -> Result<PRChecksResult, AppError> {
+    with_forge(&app, repo_path, move |forge, repo_path| {
+        forge.pr_checks(repo_path, pr_number)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_pr_details(
+    app: AppHandle,
+    repo_path: String,
+    pr_number: u64,
+) -> Result<PRDetailedInfo, AppError> {
+    with_forge(&app, repo_path, move |forge, repo_path| {
+        forge.pr_details(repo_path, pr_number)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_pr(
+    app: AppHandle,
+    repo_path: String,
+    title: String,
+    body: Option<String>,
+    base: Option<String>,
+    draft: bool,
+    operation_id: Option<String>,
+) -> Result<CreatePRResult, AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    let base_app = app.clone();
+    with_forge(&app, repo_path, move |forge, repo_path| {
+        let base = match base.filter(|b| !b.trim().is_empty()) {
+            Some(base) => base,
+            None => resolve_base_branch(&base_app, &open_repo(repo_path)?)?,
+        };
+        let pr = NewPullRequest {
+            title,
+            body: body.unwrap_or_default(),
+            base,
+            draft,
+        };
+        forge.create_pr(repo_path, &pr, operation_id.as_deref())
+    })
+    .await
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForgeAuth {
+    pub forge: ForgeKind,
+    pub host: String,
+    /// Login on `host`, `None` when the forge can't be reached with credentials
+    pub user: Option<String>,
+}
+
+/// Which forge hosts `repo_path`'s origin and who is signed in there, so the UI can
+/// label PRs vs MRs and only poll repositories it can reach.
+#[tauri::command]
+pub async fn check_forge_auth(app: AppHandle, repo_path: String) -> Result<ForgeAuth, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let remote = remote_repo(&repo_path)?;
+        let user = forge_for(&app, &repo_path).current_user(&remote.host).ok();
+        Ok(ForgeAuth {
+            forge: forge_kind(&app, &remote.host),
+            host: remote.host,
+            user,
+        })
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn get_forge_settings(app: AppHandle) -> ForgeSettings {
+    load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_forge_settings(app: AppHandle, settings: ForgeSettings) -> Result<(), AppError> {
+    save_json(&app, SETTINGS_FILE, &settings)
+}
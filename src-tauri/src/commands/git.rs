@@ -1,11 +1,22 @@
 use chrono::{DateTime, Utc};
 use git2::{BranchType, Delta, DiffOptions, Repository, WorktreeAddOptions};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::process::Command;
-use regex::Regex;
-
-use super::cli_tools::find_cli_tool;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::forge::forge_for;
+use super::error::AppError;
+use super::runner::{Operation, ProcessRunner};
+use super::sparse::{apply_sparse_patterns, is_skip_worktree, resolve_sparse_spec, SparseSpec};
+use super::registry::{repo_settings, run_hook};
+use super::naming::{
+    common_git_dir, configured_worktree_root, ensure_worktrees_excluded, generate_unique_name,
+    validate_branch_name, validate_worktree_name, worktree_name_from_branch,
+};
+
+/// Pushing large histories over slow links can take a while
+const PUSH_TIMEOUT: Duration = Duration::from_secs(600);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RepoInfo {
@@ -19,6 +30,27 @@ pub struct DiffStats {
     pub deletions: usize,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DirtyStats {
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AheadBehind {
+    pub reference: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LastCommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub time: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorktreeInfo {
     pub name: String,
@@ -26,6 +58,13 @@ pub struct WorktreeInfo {
     pub branch: Option<String>,
     pub last_modified: Option<String>,
     pub diff_stats: Option<DiffStats>,
+    pub dirty: Option<DirtyStats>,
+    pub upstream: Option<AheadBehind>,
+    pub base: Option<AheadBehind>,
+    pub last_commit: Option<LastCommitInfo>,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    pub prunable: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,18 +107,20 @@ fn get_worktree_branch(repo_path: &std::path::Path) -> Option<String> {
     head.shorthand().map(String::from)
 }
 
-fn get_diff_stats_vs_origin_default(repo_path: &std::path::Path) -> Option<DiffStats> {
-    let repo = Repository::open(repo_path).ok()?;
-    
-    let head = repo.head().ok()?;
-    let head_commit = head.peel_to_commit().ok()?;
-    
-    let base_commit = repo
-        .find_branch("origin/main", BranchType::Remote)
+/// Resolves the default base branch (origin/main, origin/master, then local main/master).
+fn find_default_base_branch(repo: &Repository) -> Option<git2::Branch<'_>> {
+    repo.find_branch("origin/main", BranchType::Remote)
         .or_else(|_| repo.find_branch("origin/master", BranchType::Remote))
         .or_else(|_| repo.find_branch("main", BranchType::Local))
         .or_else(|_| repo.find_branch("master", BranchType::Local))
-        .ok()?
+        .ok()
+}
+
+fn get_diff_stats_vs_origin_default(repo: &Repository) -> Option<DiffStats> {
+    let head = repo.head().ok()?;
+    let head_commit = head.peel_to_commit().ok()?;
+    
+    let base_commit = find_default_base_branch(repo)?
         .get()
         .peel_to_commit()
         .ok()?;
@@ -100,10 +141,158 @@ fn get_diff_stats_vs_origin_default(repo_path: &std::path::Path) -> Option<DiffS
     })
 }
 
+fn get_dirty_stats(repo: &Repository) -> Option<DirtyStats> {
+    let statuses = repo
+        .statuses(Some(
+            git2::StatusOptions::new()
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .include_ignored(false),
+        ))
+        .ok()?;
+
+    let index = repo.index().ok()?;
+    let mut stats = DirtyStats::default();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status == git2::Status::WT_DELETED
+            && entry.path().is_some_and(|p| is_skip_worktree(&index, p))
+        {
+            continue;
+        }
+        if status.intersects(
+            git2::Status::INDEX_NEW
+                | git2::Status::INDEX_MODIFIED
+                | git2::Status::INDEX_DELETED
+                | git2::Status::INDEX_RENAMED
+                | git2::Status::INDEX_TYPECHANGE,
+        ) {
+            stats.staged += 1;
+        }
+        if status.contains(git2::Status::WT_NEW) {
+            stats.untracked += 1;
+        } else if status.intersects(
+            git2::Status::WT_MODIFIED
+                | git2::Status::WT_DELETED
+                | git2::Status::WT_RENAMED
+                | git2::Status::WT_TYPECHANGE
+                | git2::Status::CONFLICTED,
+        ) {
+            stats.unstaged += 1;
+        }
+    }
+
+    Some(stats)
+}
+
+fn get_base_ahead_behind(repo: &Repository) -> Option<AheadBehind> {
+    let head_oid = repo.head().ok()?.target()?;
+    let base_branch = find_default_base_branch(repo)?;
+    let base_name = base_branch.name().ok()??.to_string();
+    let base_oid = base_branch.get().target()?;
+
+    let (ahead, behind) = repo.graph_ahead_behind(head_oid, base_oid).ok()?;
+    Some(AheadBehind {
+        reference: base_name,
+        ahead,
+        behind,
+    })
+}
+
+fn get_last_commit_info(repo: &Repository) -> Option<LastCommitInfo> {
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    let time = DateTime::<Utc>::from_timestamp(commit.time().seconds(), 0)?;
+
+    Some(LastCommitInfo {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        time: time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    })
+}
+
+fn unreadable_worktree_info(name: String, path: &std::path::Path) -> WorktreeInfo {
+    WorktreeInfo {
+        name,
+        path: path.to_string_lossy().to_string(),
+        branch: None,
+        last_modified: None,
+        diff_stats: None,
+        dirty: None,
+        upstream: None,
+        base: None,
+        last_commit: None,
+        locked: false,
+        lock_reason: None,
+        prunable: false,
+    }
+}
+
+/// Collects branch, diff, dirty and ahead/behind information for a worktree
+/// using a single repository handle.
+fn build_worktree_info(name: String, path: &std::path::Path) -> WorktreeInfo {
+    worktree_info(name, path, true)
+}
+
+/// Without `diff_stats` the diff against the base branch, a full walk on large
+/// repos, is skipped; the main checkout has no branch of its own to diff.
+fn worktree_info(name: String, path: &std::path::Path, diff_stats: bool) -> WorktreeInfo {
+    let last_modified = get_last_modified(path);
+
+    let repo = match Repository::open(path) {
+        Ok(repo) => repo,
+        Err(_) => {
+            let mut info = unreadable_worktree_info(name, path);
+            info.last_modified = last_modified;
+            return info;
+        }
+    };
+
+    let branch = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
+    let upstream = get_upstream_info(&repo).map(|(reference, ahead, behind)| AheadBehind {
+        reference,
+        ahead,
+        behind,
+    });
+
+    WorktreeInfo {
+        name,
+        path: path.to_string_lossy().to_string(),
+        branch,
+        last_modified,
+        diff_stats: diff_stats
+            .then(|| get_diff_stats_vs_origin_default(&repo))
+            .flatten(),
+        dirty: get_dirty_stats(&repo),
+        upstream,
+        base: get_base_ahead_behind(&repo),
+        last_commit: get_last_commit_info(&repo),
+        locked: false,
+        lock_reason: None,
+        prunable: false,
+    }
+}
+
+fn apply_worktree_state(info: &mut WorktreeInfo, wt: &git2::Worktree) {
+    if let Ok(git2::WorktreeLockStatus::Locked(reason)) = wt.is_locked() {
+        info.locked = true;
+        info.lock_reason = reason.filter(|r| !r.is_empty());
+    }
+    info.prunable = wt.is_prunable(None).unwrap_or(false);
+}
+
+/// Reads the worktree path recorded in `.git/worktrees/<name>/gitdir`, for
+/// worktrees that libgit2 can no longer open.
+fn read_recorded_worktree_path(repo: &Repository, name: &str) -> Option<PathBuf> {
+    let gitdir_file = repo.path().join("worktrees").join(name).join("gitdir");
+    let contents = std::fs::read_to_string(gitdir_file).ok()?;
+    let dot_git = PathBuf::from(contents.trim());
+    dot_git.parent().map(|p| p.to_path_buf())
+}
+
 #[tauri::command]
-pub fn discover_repository(path: String) -> Result<RepoInfo, String> {
+pub fn discover_repository(path: String) -> Result<RepoInfo, AppError> {
     let path_buf = PathBuf::from(&path);
-    let repo = Repository::discover(&path_buf).map_err(|e| e.message().to_string())?;
+    let repo = Repository::discover(&path_buf)?;
 
     let workdir = repo
         .workdir()
@@ -123,40 +312,31 @@ pub fn discover_repository(path: String) -> Result<RepoInfo, String> {
 }
 
 #[tauri::command]
-pub fn list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, String> {
-    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
-    let worktrees = repo.worktrees().map_err(|e| e.message().to_string())?;
+pub fn list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, AppError> {
+    let repo = open_repo(&repo_path)?;
+    let worktrees = repo.worktrees()?;
 
     let mut result = Vec::new();
 
     let main_workdir = repo.workdir().map(|p| p.to_path_buf());
     if let Some(main_path) = main_workdir {
-        let branch = get_worktree_branch(&main_path);
-        let last_modified = get_last_modified(&main_path);
-
-        result.push(WorktreeInfo {
-            name: "main".to_string(),
-            path: main_path.to_string_lossy().to_string(),
-            branch,
-            last_modified,
-            diff_stats: None,
-        });
+        result.push(worktree_info("main".to_string(), &main_path, false));
     }
 
     for wt_name in worktrees.iter().flatten() {
-        if let Ok(wt) = repo.find_worktree(wt_name) {
-            let wt_path = wt.path().to_path_buf();
-            let branch = get_worktree_branch(&wt_path);
-            let last_modified = get_last_modified(&wt_path);
-            let diff_stats = get_diff_stats_vs_origin_default(&wt_path);
-
-            result.push(WorktreeInfo {
-                name: wt_name.to_string(),
-                path: wt_path.to_string_lossy().to_string(),
-                branch,
-                last_modified,
-                diff_stats,
-            });
+        match repo.find_worktree(wt_name) {
+            Ok(wt) => {
+                let wt_path = wt.path().to_path_buf();
+                let mut info = build_worktree_info(wt_name.to_string(), &wt_path);
+                apply_worktree_state(&mut info, &wt);
+                result.push(info);
+            }
+            Err(_) => {
+                let wt_path = read_recorded_worktree_path(&repo, wt_name).unwrap_or_default();
+                let mut info = unreadable_worktree_info(wt_name.to_string(), &wt_path);
+                info.prunable = true;
+                result.push(info);
+            }
         }
     }
 
@@ -164,11 +344,8 @@ pub fn list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, String> {
 }
 
 #[tauri::command]
-pub fn get_worktree_info(worktree_path: String) -> Result<WorktreeInfo, String> {
+pub fn get_worktree_info(worktree_path: String) -> Result<WorktreeInfo, AppError> {
     let path = PathBuf::from(&worktree_path);
-    let branch = get_worktree_branch(&path);
-    let last_modified = get_last_modified(&path);
-    let diff_stats = get_diff_stats_vs_origin_default(&path);
 
     let name = path
         .file_name()
@@ -176,32 +353,27 @@ pub fn get_worktree_info(worktree_path: String) -> Result<WorktreeInfo, String>
         .unwrap_or("unknown")
         .to_string();
 
-    Ok(WorktreeInfo {
-        name,
-        path: worktree_path,
-        branch,
-        last_modified,
-        diff_stats,
-    })
+    let mut info = build_worktree_info(name, &path);
+    info.path = worktree_path;
+    Ok(info)
 }
 
 #[tauri::command]
-pub fn get_worktree_branch_name(worktree_path: String) -> Result<Option<String>, String> {
+pub fn get_worktree_branch_name(worktree_path: String) -> Result<Option<String>, AppError> {
     let path = PathBuf::from(&worktree_path);
     Ok(get_worktree_branch(&path))
 }
 
 #[tauri::command]
-pub fn list_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
-    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
+pub fn list_branches(repo_path: String) -> Result<Vec<BranchInfo>, AppError> {
+    let repo = open_repo(&repo_path)?;
 
     let mut branches = Vec::new();
 
     let head_name = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
 
     for branch_result in repo
-        .branches(Some(BranchType::Local))
-        .map_err(|e| e.message().to_string())?
+        .branches(Some(BranchType::Local))?
     {
         if let Ok((branch, _)) = branch_result {
             if let Some(name) = branch.name().ok().flatten() {
@@ -215,8 +387,7 @@ pub fn list_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
     }
 
     for branch_result in repo
-        .branches(Some(BranchType::Remote))
-        .map_err(|e| e.message().to_string())?
+        .branches(Some(BranchType::Remote))?
     {
         if let Ok((branch, _)) = branch_result {
             if let Some(name) = branch.name().ok().flatten() {
@@ -234,307 +405,969 @@ pub fn list_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
     Ok(branches)
 }
 
-const CITY_NAMES: &[&str] = &[
-    "tokyo", "paris", "london", "berlin", "sydney", "toronto", "mumbai", "cairo",
-    "rio", "seoul", "dublin", "oslo", "vienna", "prague", "lisbon", "athens",
-    "rome", "madrid", "amsterdam", "brussels", "zurich", "stockholm", "helsinki",
-    "warsaw", "budapest", "bangkok", "singapore", "jakarta", "manila", "hanoi",
-    "beijing", "shanghai", "hongkong", "taipei", "osaka", "kyoto", "melbourne",
-    "auckland", "vancouver", "montreal", "chicago", "boston", "seattle", "denver",
-    "austin", "miami", "atlanta", "phoenix", "portland", "detroit", "dallas",
-    "houston", "philadelphia", "sandiego", "sanfrancisco", "losangeles", "newyork",
-    "nairobi", "lagos", "capetown", "casablanca", "tunis", "algiers", "accra",
-    "lima", "bogota", "santiago", "buenosaires", "montevideo", "quito", "caracas",
-    "havana", "mexicocity", "guadalajara", "panama", "sanjose", "kingston",
-];
-
-fn generate_unique_worktree_name(repo: &Repository) -> Result<String, String> {
-    use rand::Rng;
-    
-    let mut rng = rand::rng();
-    
-    for _ in 0..100 {
-        let city = CITY_NAMES[rng.random_range(0..CITY_NAMES.len())];
-        let num: u32 = rng.random_range(100..999);
-        let name = format!("{}-{}", num, city);
-        
-        let branch_exists = repo.find_branch(&name, BranchType::Local).is_ok();
-        let ref_exists = repo.find_reference(&format!("refs/heads/{}", name)).is_ok();
-        
-        if !branch_exists && !ref_exists {
-            return Ok(name);
+pub fn resolve_worktree_path(
+    repo: &Repository,
+    repo_path: &str,
+    worktree_name: &str,
+    target_path: Option<String>,
+) -> Result<PathBuf, AppError> {
+    if let Some(p) = target_path {
+        return Ok(PathBuf::from(p));
+    }
+
+    let repo_dir = PathBuf::from(repo_path);
+    let root = match configured_worktree_root(repo, &repo_dir) {
+        Some(root) => root,
+        None => {
+            ensure_worktrees_excluded(repo)?;
+            repo_dir.join(".worktrees")
         }
+    };
+
+    Ok(root.join(worktree_name))
+}
+
+/// Opens a repository, reporting a missing or non-git path as `RepoNotFound`.
+pub fn open_repo(path: impl AsRef<Path>) -> Result<Repository, AppError> {
+    let path = path.as_ref();
+    Repository::open(path).map_err(|e| match e.code() {
+        git2::ErrorCode::NotFound => AppError::RepoNotFound {
+            path: path.to_string_lossy().to_string(),
+        },
+        _ => AppError::from(e),
+    })
+}
+
+pub fn run_git(dir: &str, args: &[&str]) -> Result<String, AppError> {
+    Ok(ProcessRunner::new("git").args(args).cwd(dir).run()?)
+}
+
+/// `run_git` for clones, fetches and checkouts that can legitimately take minutes:
+/// no timeout, but cancellable through `operation_id`.
+pub fn run_git_long(
+    dir: &str,
+    args: &[&str],
+    operation_id: Option<&str>,
+) -> Result<String, AppError> {
+    Ok(ProcessRunner::new("git")
+        .args(args)
+        .cwd(dir)
+        .no_timeout()
+        .operation(operation_id)
+        .run()?)
+}
+
+fn add_worktree_for_branch(
+    repo: &Repository,
+    worktree_name: &str,
+    wt_path: &std::path::Path,
+    branch: git2::Branch<'_>,
+) -> Result<(), AppError> {
+    if let Some(parent) = wt_path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
 
-    Err("Could not generate unique worktree name".to_string())
+    let mut opts = WorktreeAddOptions::new();
+    let branch_ref = branch.into_reference();
+    opts.reference(Some(&branch_ref));
+
+    repo.worktree(worktree_name, wt_path, Some(&opts))?;
+
+    Ok(())
 }
 
+/// Returns the local branch tracking `remote_branch` (e.g. `origin/feature`),
+/// creating it if needed. An existing branch that is behind is fast-forwarded;
+/// one that has diverged is rejected.
+fn local_branch_for_remote<'r>(
+    repo: &'r Repository,
+    remote_branch: &str,
+) -> Result<git2::Branch<'r>, AppError> {
+    let remote = repo
+        .find_branch(remote_branch, BranchType::Remote)
+        .map_err(|e| AppError::NotFound(format!("Remote branch not found: {}", e.message())))?;
+
+    let local_name = remote_branch
+        .split_once('/')
+        .map(|(_, name)| name)
+        .unwrap_or(remote_branch);
+
+    let remote_oid = remote
+        .get()
+        .target()
+        .ok_or_else(|| AppError::Git(format!("{} has no target", remote_branch)))?;
+
+    if let Ok(mut local) = repo.find_branch(local_name, BranchType::Local) {
+        let local_oid = local
+            .get()
+            .target()
+            .ok_or_else(|| AppError::Git(format!("{} has no target", local_name)))?;
+        if local_oid == remote_oid || repo.graph_descendant_of(local_oid, remote_oid)? {
+            return Ok(local);
+        }
+        if !repo.graph_descendant_of(remote_oid, local_oid)? {
+            return Err(AppError::InvalidInput(format!(
+                "Local branch {} has diverged from {}",
+                local_name, remote_branch
+            )));
+        }
+        // Checked-out branches are left alone; adding the worktree reports them
+        if !branch_checked_out(repo, local_name) {
+            let message = format!("Fast-forward {} to {}", local_name, remote_branch);
+            local.get_mut().set_target(remote_oid, &message)?;
+            local = repo.find_branch(local_name, BranchType::Local)?;
+        }
+        return Ok(local);
+    }
+
+    let commit = remote
+        .get()
+        .peel_to_commit()
+        .map_err(|e| AppError::Git(format!("Cannot get commit: {}", e.message())))?;
+    let mut local = repo
+        .branch(local_name, &commit, false)
+        .map_err(|e| AppError::Git(format!("Cannot create branch: {}", e.message())))?;
+    local
+        .set_upstream(Some(remote_branch))
+        .map_err(|e| AppError::Git(format!("Cannot set upstream: {}", e.message())))?;
+
+    Ok(local)
+}
+
+/// Whether `branch` is the HEAD of the main checkout or of any worktree.
+fn branch_checked_out(repo: &Repository, branch: &str) -> bool {
+    let refname = format!("refs/heads/{}", branch);
+    let is_head = |r: &Repository| {
+        r.head()
+            .ok()
+            .is_some_and(|h| h.name() == Some(refname.as_str()))
+    };
+    if is_head(repo) {
+        return true;
+    }
+    let Ok(worktrees) = repo.worktrees() else {
+        return false;
+    };
+    worktrees
+        .iter()
+        .flatten()
+        .filter_map(|name| repo.find_worktree(name).ok())
+        .filter_map(|wt| Repository::open_from_worktree(&wt).ok())
+        .any(|r| is_head(&r))
+}
+
+/// Finds the default branch name (main or master) that exists on origin.
+pub fn detect_remote_base_branch(repo: &Repository) -> Result<&'static str, AppError> {
+    if repo.find_branch("origin/main", BranchType::Remote).is_ok() {
+        Ok("main")
+    } else if repo.find_branch("origin/master", BranchType::Remote).is_ok() {
+        Ok("master")
+    } else {
+        Err(AppError::BaseBranchMissing)
+    }
+}
+
+/// The branch work in `repo` is compared against and merged into: the base branch
+/// configured for the repository, else origin's main/master. `repo` may be a worktree.
+pub fn resolve_base_branch(app: &AppHandle, repo: &Repository) -> Result<String, AppError> {
+    let common_dir = common_git_dir(repo);
+    let main_path = common_dir.parent().unwrap_or(&common_dir);
+    let settings = repo_settings(app, &main_path.to_string_lossy());
+    match settings.base_branch.filter(|b| !b.is_empty()) {
+        Some(base_branch) => Ok(base_branch),
+        None => Ok(detect_remote_base_branch(repo)?.to_string()),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmoduleMode {
+    /// Leave submodule directories empty
+    Skip,
+    /// Borrow objects from the main checkout's `.git/modules` where available
+    Reference,
+    /// Clone every submodule from its remote
+    Clone,
+}
+
+fn init_worktree_submodules(
+    repo_path: &str,
+    wt_path: &std::path::Path,
+    mode: SubmoduleMode,
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    if mode == SubmoduleMode::Skip {
+        return Ok(());
+    }
+
+    let wt_repo = open_repo(wt_path)?;
+    let submodules = wt_repo.submodules()?;
+    if submodules.is_empty() {
+        return Ok(());
+    }
+
+    let main_repo = open_repo(repo_path)?;
+    let main_modules_dir = main_repo.path().join("modules");
+    let wt_dir = wt_path.to_string_lossy().to_string();
+
+    for sm in &submodules {
+        let sm_path = sm.path().to_string_lossy().to_string();
+        let sm_name = sm.name().map(String::from).unwrap_or_else(|| sm_path.clone());
+        let reference_dir = main_modules_dir.join(&sm_name);
+
+        if mode == SubmoduleMode::Reference && reference_dir.exists() {
+            let reference = reference_dir.to_string_lossy().to_string();
+            run_git_long(
+                &wt_dir,
+                &["submodule", "update", "--init", "--reference", &reference, "--", &sm_path],
+                operation_id,
+            )?;
+        } else {
+            run_git_long(
+                &wt_dir,
+                &["submodule", "update", "--init", "--", &sm_path],
+                operation_id,
+            )?;
+        }
+    }
+
+    // Nested submodules are cloned normally; --reference only applies to the top level
+    run_git_long(
+        &wt_dir,
+        &["submodule", "update", "--init", "--recursive"],
+        operation_id,
+    )?;
+
+    Ok(())
+}
+
+/// Creates a worktree with a generated name. Submodule clones and the post-create
+/// hook can be cancelled through `operation_id`.
 #[tauri::command]
-pub fn create_worktree_auto(repo_path: String) -> Result<WorktreeInfo, String> {
-    use std::process::Command;
-    
-    Command::new("git")
+pub async fn create_worktree_auto(
+    app: AppHandle,
+    repo_path: String,
+    ticket: Option<String>,
+    submodules: Option<SubmoduleMode>,
+    operation_id: Option<String>,
+) -> Result<WorktreeInfo, AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    tokio::task::spawn_blocking(move || {
+        create_worktree_auto_blocking(
+            &app,
+            repo_path,
+            ticket,
+            submodules,
+            operation_id.as_deref(),
+        )
+    })
+    .await?
+}
+
+/// `create_worktree_auto` for callers already off the async runtime.
+pub fn create_worktree_auto_blocking(
+    app: &AppHandle,
+    repo_path: String,
+    ticket: Option<String>,
+    submodules: Option<SubmoduleMode>,
+    operation_id: Option<&str>,
+) -> Result<WorktreeInfo, AppError> {
+    ProcessRunner::new("git")
         .args(["worktree", "prune"])
-        .current_dir(&repo_path)
+        .cwd(&repo_path)
         .output()
         .ok();
     
-    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
+    let repo = open_repo(&repo_path)?;
     
-    let worktree_name = generate_unique_worktree_name(&repo)?;
+    let generated = generate_unique_name(&repo, ticket.as_deref())?;
+    let worktree_name = generated.worktree_name;
+    let branch_name = generated.branch_name;
     
-    // Find default branch (origin/main or origin/master)
-    let base_branch = if repo.find_branch("origin/main", BranchType::Remote).is_ok() {
-        "main"
-    } else if repo.find_branch("origin/master", BranchType::Remote).is_ok() {
-        "master"
-    } else {
-        return Err("Cannot find origin/main or origin/master".to_string());
+    let settings = repo_settings(app, &repo_path);
+    let base_branch = match settings.base_branch.as_deref().filter(|b| !b.is_empty()) {
+        Some(base_branch) => base_branch.to_string(),
+        None => detect_remote_base_branch(&repo)?.to_string(),
     };
 
-    let worktrees_dir = PathBuf::from(&repo_path).join(".worktrees");
-    if !worktrees_dir.exists() {
-        std::fs::create_dir_all(&worktrees_dir).map_err(|e| e.to_string())?;
+    let wt_path = resolve_worktree_path(&repo, &repo_path, &worktree_name, None)?;
+
+    let base_commit = find_base_commit(&repo, &base_branch)?;
+
+    add_worktree_on_new_branch(
+        &repo,
+        &repo_path,
+        &worktree_name,
+        &wt_path,
+        &branch_name,
+        &base_commit,
+        None,
+        operation_id,
+    )?;
+    set_up_new_worktree(
+        app,
+        &repo,
+        &repo_path,
+        &worktree_name,
+        &wt_path,
+        &branch_name,
+        submodules,
+        operation_id,
+    )?;
+
+    Ok(build_worktree_info(worktree_name, &wt_path))
+}
+
+/// Creates `branch_name` at `base_commit` and checks it out in a new worktree,
+/// removing both again if the worktree can't be added or populated.
+///
+/// libgit2 can't skip the initial checkout, so sparse worktrees are added with
+/// `--no-checkout` and populated after the sparse patterns are in place.
+#[allow(clippy::too_many_arguments)]
+fn add_worktree_on_new_branch(
+    repo: &Repository,
+    repo_path: &str,
+    worktree_name: &str,
+    wt_path: &Path,
+    branch_name: &str,
+    base_commit: &git2::Commit<'_>,
+    sparse: Option<(bool, Vec<String>)>,
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    if let Some(parent) = wt_path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
-    
-    let wt_path = worktrees_dir.join(&worktree_name);
 
-    let remote_name = format!("origin/{}", base_branch);
-    let base_commit = repo
-        .find_branch(&remote_name, BranchType::Remote)
-        .map_err(|e| format!("Base branch not found: {}", e.message()))?
-        .get()
-        .peel_to_commit()
-        .map_err(|e| format!("Cannot get commit: {}", e.message()))?;
+    if sparse.is_some() && repo.path().join("worktrees").join(worktree_name).exists() {
+        return Err(AppError::InvalidInput(format!(
+            "Worktree already exists: {}",
+            worktree_name
+        )));
+    }
 
-    let new_branch = repo
-        .branch(&worktree_name, &base_commit, false)
-        .map_err(|e| format!("Cannot create branch: {}", e.message()))?;
+    let mut new_branch = repo
+        .branch(branch_name, base_commit, false)
+        .map_err(|e| AppError::Git(format!("Cannot create branch: {}", e.message())))?;
+
+    let wt_path_str = wt_path.to_string_lossy().to_string();
+    let added = match &sparse {
+        Some(_) => {
+            run_git_long(
+                repo_path,
+                &["worktree", "add", "--no-checkout", &wt_path_str, branch_name],
+                operation_id,
+            )
+            .and_then(|_| name_cli_worktree(wt_path, worktree_name))
+        }
+        None => {
+            let mut opts = WorktreeAddOptions::new();
+            opts.reference(Some(new_branch.get()));
+            repo.worktree(worktree_name, wt_path, Some(&opts))
+                .map(|_| ())
+                .map_err(AppError::from)
+        }
+    };
+    if let Err(e) = added {
+        new_branch.delete().ok();
+        return Err(e);
+    }
 
-    let mut opts = WorktreeAddOptions::new();
-    let branch_ref = new_branch.into_reference();
-    opts.reference(Some(&branch_ref));
+    if let Some((cone, patterns)) = sparse {
+        let populated = apply_sparse_patterns(wt_path, cone, &patterns, operation_id)
+            .and_then(|_| run_git_long(&wt_path_str, &["read-tree", "-mu", "HEAD"], operation_id));
+        if let Err(e) = populated {
+            discard_new_worktree(repo, worktree_name, wt_path, Some(branch_name));
+            return Err(e);
+        }
+    }
 
-    repo.worktree(&worktree_name, &wt_path, Some(&opts))
-        .map_err(|e| e.message().to_string())?;
+    Ok(())
+}
 
-    let last_modified = get_last_modified(&wt_path);
-    let diff_stats = get_diff_stats_vs_origin_default(&wt_path);
+/// Initializes submodules and runs the post-create hook in a just-added worktree.
+/// If either fails the worktree and `branch_name`, which the caller created, are
+/// removed so that retrying doesn't collide with the leftovers.
+#[allow(clippy::too_many_arguments)]
+fn set_up_new_worktree(
+    app: &AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    worktree_name: &str,
+    wt_path: &Path,
+    branch_name: &str,
+    submodules: Option<SubmoduleMode>,
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    let submodules = submodules.unwrap_or(SubmoduleMode::Skip);
+    let result = init_worktree_submodules(repo_path, wt_path, submodules, operation_id)
+        .and_then(|_| {
+            let post_create = repo_settings(app, repo_path).hooks.post_create;
+            run_hook("post-create", post_create.as_deref(), wt_path, operation_id)
+        });
+    if result.is_err() {
+        discard_new_worktree(repo, worktree_name, wt_path, Some(branch_name));
+    }
+    result
+}
 
-    Ok(WorktreeInfo {
-        name: worktree_name.clone(),
-        path: wt_path.to_string_lossy().to_string(),
-        branch: Some(worktree_name),
-        last_modified,
-        diff_stats,
-    })
+/// Force-removes a worktree that failed setup, and `created_branch` with it.
+/// The admin dir goes first, libgit2 won't delete a branch checked out anywhere.
+pub fn discard_new_worktree(
+    repo: &Repository,
+    worktree_name: &str,
+    wt_path: &Path,
+    created_branch: Option<&str>,
+) {
+    std::fs::remove_dir_all(repo.path().join("worktrees").join(worktree_name)).ok();
+    std::fs::remove_dir_all(wt_path).ok();
+    if let Some(branch) = created_branch {
+        if let Ok(mut branch) = repo.find_branch(branch, BranchType::Local) {
+            branch.delete().ok();
+        }
+    }
 }
 
+/// `git worktree add` names a worktree after its directory; renames the admin
+/// dir so the worktree can be found by `worktree_name` like libgit2-created ones.
+fn name_cli_worktree(wt_path: &Path, worktree_name: &str) -> Result<(), AppError> {
+    let dot_git = wt_path.join(".git");
+    let contents = std::fs::read_to_string(&dot_git)?;
+    let admin_dir = contents
+        .trim()
+        .strip_prefix("gitdir:")
+        .map(|dir| PathBuf::from(dir.trim()))
+        .ok_or_else(|| AppError::Git(format!("Unexpected {}", dot_git.display())))?;
+    if admin_dir.file_name() == Some(std::ffi::OsStr::new(worktree_name)) {
+        return Ok(());
+    }
+
+    let named_dir = admin_dir.with_file_name(worktree_name);
+    std::fs::rename(&admin_dir, &named_dir)?;
+    std::fs::write(&dot_git, format!("gitdir: {}\n", named_dir.display()))?;
+    Ok(())
+}
+
+pub fn find_base_commit<'r>(repo: &'r Repository, base_branch: &str) -> Result<git2::Commit<'r>, AppError> {
+    let remote_name = format!("origin/{}", base_branch);
+    repo.find_branch(&remote_name, BranchType::Remote)
+        .or_else(|_| repo.find_branch(base_branch, BranchType::Local))
+        .map_err(|_| AppError::NotFound(format!("Base branch not found: {}", base_branch)))?
+        .get()
+        .peel_to_commit()
+        .map_err(|e| AppError::Git(format!("Cannot get commit: {}", e.message())))
+}
+
+/// Creates a worktree on a new branch from `base_branch`. With `sparse` set only
+/// the given sparse-checkout set is materialized. The checkout, submodule clones
+/// and the post-create hook can be cancelled through `operation_id`.
 #[tauri::command]
-pub fn create_worktree(
+#[allow(clippy::too_many_arguments)]
+pub async fn create_worktree(
+    app: AppHandle,
     repo_path: String,
     worktree_name: String,
     base_branch: String,
     new_branch_name: Option<String>,
     target_path: Option<String>,
-) -> Result<WorktreeInfo, String> {
-    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
+    submodules: Option<SubmoduleMode>,
+    sparse: Option<SparseSpec>,
+    operation_id: Option<String>,
+) -> Result<WorktreeInfo, AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    tokio::task::spawn_blocking(move || {
+        create_worktree_blocking(
+            &app,
+            repo_path,
+            worktree_name,
+            base_branch,
+            new_branch_name,
+            target_path,
+            submodules,
+            sparse,
+            operation_id.as_deref(),
+        )
+    })
+    .await?
+}
 
-    let wt_path = match target_path {
-        Some(p) => PathBuf::from(p),
-        None => {
-            PathBuf::from(&repo_path)
-                .join(".worktrees")
-                .join(&worktree_name)
-        }
-    };
+/// `create_worktree` for callers already off the async runtime.
+#[allow(clippy::too_many_arguments)]
+pub fn create_worktree_blocking(
+    app: &AppHandle,
+    repo_path: String,
+    worktree_name: String,
+    base_branch: String,
+    new_branch_name: Option<String>,
+    target_path: Option<String>,
+    submodules: Option<SubmoduleMode>,
+    sparse: Option<SparseSpec>,
+    operation_id: Option<&str>,
+) -> Result<WorktreeInfo, AppError> {
+    let repo = open_repo(&repo_path)?;
 
     let branch_name = new_branch_name.unwrap_or_else(|| worktree_name.clone());
+    validate_branch_name(&branch_name)?;
+    validate_worktree_name(&worktree_name)?;
+    let sparse = sparse
+        .map(|spec| resolve_sparse_spec(&repo, &spec))
+        .transpose()?;
+
+    let wt_path = resolve_worktree_path(&repo, &repo_path, &worktree_name, target_path)?;
+
+    let base_commit = find_base_commit(&repo, &base_branch)?;
+
+    add_worktree_on_new_branch(
+        &repo,
+        &repo_path,
+        &worktree_name,
+        &wt_path,
+        &branch_name,
+        &base_commit,
+        sparse,
+        operation_id,
+    )?;
+    set_up_new_worktree(
+        app,
+        &repo,
+        &repo_path,
+        &worktree_name,
+        &wt_path,
+        &branch_name,
+        submodules,
+        operation_id,
+    )?;
+
+    let mut info = build_worktree_info(worktree_name, &wt_path);
+    info.branch = Some(branch_name);
+    Ok(info)
+}
 
-    let remote_name = format!("origin/{}", base_branch);
-    let base_commit = repo
-        .find_branch(&remote_name, BranchType::Remote)
-        .or_else(|_| repo.find_branch(&base_branch, BranchType::Local))
-        .map_err(|e| format!("Base branch not found: {}", e.message()))?
-        .get()
-        .peel_to_commit()
-        .map_err(|e| format!("Cannot get commit: {}", e.message()))?;
+#[tauri::command]
+pub async fn create_worktree_from_branch(
+    repo_path: String,
+    branch: String,
+    is_remote: bool,
+    worktree_name: Option<String>,
+    target_path: Option<String>,
+) -> Result<WorktreeInfo, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = open_repo(&repo_path)?;
 
-    let new_branch = repo
-        .branch(&branch_name, &base_commit, false)
-        .map_err(|e| format!("Cannot create branch: {}", e.message()))?;
+        let local_branch = if is_remote {
+            local_branch_for_remote(&repo, &branch)?
+        } else {
+            repo.find_branch(&branch, BranchType::Local)
+                .map_err(|e| AppError::NotFound(format!("Branch not found: {}", e.message())))?
+        };
+        let branch_name = local_branch
+            .name()
+            .ok()
+            .flatten()
+            .map(String::from)
+            .ok_or("Branch name is not valid UTF-8")?;
 
-    let mut opts = WorktreeAddOptions::new();
-    let branch_ref = new_branch.into_reference();
-    opts.reference(Some(&branch_ref));
+        let worktree_name = worktree_name.unwrap_or_else(|| worktree_name_from_branch(&branch_name));
+        validate_worktree_name(&worktree_name)?;
+        let wt_path = resolve_worktree_path(&repo, &repo_path, &worktree_name, target_path)?;
 
-    repo.worktree(&worktree_name, &wt_path, Some(&opts))
-        .map_err(|e| e.message().to_string())?;
+        add_worktree_for_branch(&repo, &worktree_name, &wt_path, local_branch)?;
 
-    let last_modified = get_last_modified(&wt_path);
-    let diff_stats = get_diff_stats_vs_origin_default(&wt_path);
+        Ok::<WorktreeInfo, AppError>(build_worktree_info(worktree_name, &wt_path))
+    })
+    .await?
+}
 
-    Ok(WorktreeInfo {
-        name: worktree_name,
-        path: wt_path.to_string_lossy().to_string(),
-        branch: Some(branch_name),
-        last_modified,
-        diff_stats,
+#[tauri::command]
+pub async fn create_worktree_from_pr(
+    app: AppHandle,
+    repo_path: String,
+    pr_number: u64,
+    worktree_name: Option<String>,
+    target_path: Option<String>,
+    operation_id: Option<String>,
+) -> Result<WorktreeInfo, AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    tokio::task::spawn_blocking(move || {
+        let forge = forge_for(&app, &repo_path);
+        let head = forge.pr_head(&repo_path, pr_number)?;
+
+        // Same-repo PRs get a normal tracking branch so pushes go back to the PR;
+        // forks are fetched from the PR head ref into a local pr-<number> branch.
+        let branch_name = if head.is_cross_repository {
+            let branch_name = format!("pr-{}", pr_number);
+            let refspec = format!("+{}:refs/heads/{}", forge.head_ref(pr_number), branch_name);
+            run_git_long(&repo_path, &["fetch", "origin", &refspec], operation_id.as_deref())?;
+            branch_name
+        } else {
+            run_git_long(
+                &repo_path,
+                &["fetch", "origin", &head.head_ref_name],
+                operation_id.as_deref(),
+            )?;
+            head.head_ref_name.clone()
+        };
+
+        let repo = open_repo(&repo_path)?;
+        let local_branch = if head.is_cross_repository {
+            repo.find_branch(&branch_name, BranchType::Local)
+                .map_err(|e| AppError::NotFound(format!("Branch not found: {}", e.message())))?
+        } else {
+            local_branch_for_remote(&repo, &format!("origin/{}", branch_name))?
+        };
+
+        let worktree_name = worktree_name.unwrap_or_else(|| format!("pr-{}", pr_number));
+        validate_worktree_name(&worktree_name)?;
+        let wt_path = resolve_worktree_path(&repo, &repo_path, &worktree_name, target_path)?;
+
+        add_worktree_for_branch(&repo, &worktree_name, &wt_path, local_branch)?;
+
+        Ok::<WorktreeInfo, AppError>(build_worktree_info(worktree_name, &wt_path))
     })
+    .await?
 }
 
 #[tauri::command]
-pub async fn delete_worktree(repo_path: String, worktree_name: String, force: bool) -> Result<(), String> {
+pub async fn create_worktree_from_commit(
+    repo_path: String,
+    rev: String,
+    worktree_name: Option<String>,
+    target_path: Option<String>,
+) -> Result<WorktreeInfo, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
-        let worktree = repo
-            .find_worktree(&worktree_name)
-            .map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&repo_path)?;
+        let commit = repo
+            .revparse_single(&rev)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|e| AppError::NotFound(format!("Cannot resolve {}: {}", rev, e.message())))?;
+
+        let worktree_name = worktree_name.unwrap_or_else(|| worktree_name_from_branch(&rev));
+        validate_worktree_name(&worktree_name)?;
+        let wt_path = resolve_worktree_path(&repo, &repo_path, &worktree_name, target_path)?;
+        if let Some(parent) = wt_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
-        let wt_path = worktree.path().to_path_buf();
+        // libgit2 can't add a detached worktree, so it is added on a throwaway
+        // branch that is deleted once HEAD is detached. Unlike `git worktree add
+        // --detach` this keeps the worktree named `worktree_name` for any path.
+        let temp_name = format!("autopilot-detached/{}", worktree_name);
+        let mut temp_branch = repo.branch(&temp_name, &commit, false)?;
+        let added = (|| {
+            let mut opts = WorktreeAddOptions::new();
+            let temp_ref = repo.find_reference(&format!("refs/heads/{}", temp_name))?;
+            opts.reference(Some(&temp_ref));
+            let worktree = repo.worktree(&worktree_name, &wt_path, Some(&opts))?;
+            Repository::open_from_worktree(&worktree)?.set_head_detached(commit.id())
+        })();
+        temp_branch.delete().ok();
+        added?;
+
+        Ok::<WorktreeInfo, AppError>(build_worktree_info(worktree_name, &wt_path))
+    })
+    .await?
+}
 
-        if force {
-            if wt_path.exists() {
-                std::fs::remove_dir_all(&wt_path).map_err(|e| e.to_string())?;
-            }
-            
-            let git_worktrees_dir = PathBuf::from(&repo_path)
-                .join(".git")
-                .join("worktrees")
-                .join(&worktree_name);
-            if git_worktrees_dir.exists() {
-                std::fs::remove_dir_all(&git_worktrees_dir).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn update_worktree_submodules(
+    repo_path: String,
+    worktree_path: String,
+    mode: SubmoduleMode,
+    operation_id: Option<String>,
+) -> Result<(), AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    tokio::task::spawn_blocking(move || {
+        init_worktree_submodules(
+            &repo_path,
+            std::path::Path::new(&worktree_path),
+            mode,
+            operation_id.as_deref(),
+        )
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn delete_worktree(
+    app: AppHandle,
+    repo_path: String,
+    worktree_name: String,
+    force: bool,
+) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        delete_worktree_blocking(&app, repo_path, worktree_name, force)
+    })
+    .await?
+}
+
+/// `delete_worktree` for callers already off the async runtime. The repository's
+/// pre-delete hook runs first; a failing hook stops the delete unless `force` is set.
+pub fn delete_worktree_blocking(
+    app: &AppHandle,
+    repo_path: String,
+    worktree_name: String,
+    force: bool,
+) -> Result<(), AppError> {
+    let repo = open_repo(&repo_path)?;
+    let worktree = repo
+        .find_worktree(&worktree_name)?;
+
+    let wt_path = worktree.path().to_path_buf();
+
+    if let Ok(git2::WorktreeLockStatus::Locked(reason)) = worktree.is_locked() {
+        return Err(AppError::InvalidInput(match reason.filter(|r| !r.is_empty()) {
+            Some(reason) => format!("Worktree is locked: {}", reason),
+            None => "Worktree is locked".to_string(),
+        }));
+    }
+
+    if wt_path.exists() {
+        let pre_delete = repo_settings(app, &repo_path).hooks.pre_delete;
+        if let Err(e) = run_hook("pre-delete", pre_delete.as_deref(), &wt_path, None) {
+            if !force {
+                return Err(e);
             }
-        } else {
-            let mut prune_opts = git2::WorktreePruneOptions::new();
-            prune_opts.valid(true);
-            prune_opts.working_tree(true);
+        }
+    }
 
-            worktree
-                .prune(Some(&mut prune_opts))
-                .map_err(|e| e.message().to_string())?;
+    if force {
+        if wt_path.exists() {
+            std::fs::remove_dir_all(&wt_path)?;
+        }
+        
+        let git_worktrees_dir = PathBuf::from(&repo_path)
+            .join(".git")
+            .join("worktrees")
+            .join(&worktree_name);
+        if git_worktrees_dir.exists() {
+            std::fs::remove_dir_all(&git_worktrees_dir)?;
+        }
+    } else {
+        let mut prune_opts = git2::WorktreePruneOptions::new();
+        prune_opts.valid(true);
+        prune_opts.working_tree(true);
+
+        worktree
+            .prune(Some(&mut prune_opts))?;
+
+        if wt_path.exists() {
+            std::fs::remove_dir_all(&wt_path)?;
+        }
+    }
+
+    Ok(())
+}
 
-            if wt_path.exists() {
-                std::fs::remove_dir_all(&wt_path).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn lock_worktree(
+    repo_path: String,
+    worktree_name: String,
+    reason: Option<String>,
+) -> Result<(), AppError> {
+    let repo = open_repo(&repo_path)?;
+    let worktree = repo
+        .find_worktree(&worktree_name)?;
+
+    worktree
+        .lock(reason.as_deref().filter(|r| !r.is_empty()))
+        .map_err(|e| AppError::Git(format!("Cannot lock worktree: {}", e.message())))
+}
+
+#[tauri::command]
+pub fn unlock_worktree(repo_path: String, worktree_name: String) -> Result<(), AppError> {
+    let repo = open_repo(&repo_path)?;
+    let worktree = repo
+        .find_worktree(&worktree_name)?;
+
+    worktree
+        .unlock()
+        .map_err(|e| AppError::Git(format!("Cannot unlock worktree: {}", e.message())))
+}
+
+#[tauri::command]
+pub async fn prune_worktrees(repo_path: String) -> Result<Vec<String>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = open_repo(&repo_path)?;
+        let worktrees = repo.worktrees()?;
+
+        let mut pruned = Vec::new();
+        for wt_name in worktrees.iter().flatten() {
+            let wt = match repo.find_worktree(wt_name) {
+                Ok(wt) => wt,
+                Err(_) => continue,
+            };
+
+            // Locked worktrees are never prunable, so they are skipped here
+            if wt.is_prunable(None).unwrap_or(false) {
+                wt.prune(None)
+                    .map_err(|e| {
+                        AppError::Git(format!("Cannot prune {}: {}", wt_name, e.message()))
+                    })?;
+                pruned.push(wt_name.to_string());
             }
         }
 
-        Ok::<(), String>(())
+        Ok::<Vec<String>, AppError>(pruned)
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn get_changed_files(worktree_path: String) -> Result<Vec<ChangedFile>, String> {
+pub async fn move_worktree(
+    repo_path: String,
+    worktree_name: String,
+    new_path: String,
+) -> Result<WorktreeInfo, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
-        
-        let base_branch_commit = repo
-            .find_branch("origin/main", BranchType::Remote)
-            .or_else(|_| repo.find_branch("origin/master", BranchType::Remote))
-            .map_err(|e| format!("Cannot find origin/main or origin/master: {}", e.message()))?
-            .get()
-            .peel_to_commit()
-            .map_err(|e| format!("Cannot get base commit: {}", e.message()))?;
-        
-        let head_commit = repo.head()
-            .map_err(|e| format!("Cannot get HEAD: {}", e.message()))?
-            .peel_to_commit()
-            .map_err(|e| format!("Cannot get HEAD commit: {}", e.message()))?;
-        
-        let merge_base_oid = repo
-            .merge_base(base_branch_commit.id(), head_commit.id())
-            .map_err(|e| format!("Cannot find merge base: {}", e.message()))?;
-        let merge_base_commit = repo
-            .find_commit(merge_base_oid)
-            .map_err(|e| format!("Cannot get merge base commit: {}", e.message()))?;
-        let base_tree = merge_base_commit.tree().map_err(|e| e.message().to_string())?;
-        
-        let head_tree = head_commit.tree().map_err(|e| e.message().to_string())?;
-        
-        let mut diff_opts = DiffOptions::new();
-        
-        let diff = repo
-            .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut diff_opts))
-            .map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&repo_path)?;
+        let worktree = repo
+            .find_worktree(&worktree_name)?;
+        let old_path = worktree.path().to_path_buf();
+
+        ProcessRunner::new("git")
+            .args(["worktree", "move"])
+            .arg(old_path.to_string_lossy())
+            .arg(&new_path)
+            .cwd(&repo_path)
+            .label("git worktree move")
+            .run()?;
+
+        let new_path = PathBuf::from(&new_path);
+        let mut info = build_worktree_info(worktree_name.clone(), &new_path);
+        if let Ok(wt) = repo.find_worktree(&worktree_name) {
+            apply_worktree_state(&mut info, &wt);
+        }
+
+        Ok::<WorktreeInfo, AppError>(info)
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn repair_worktrees(repo_path: String, worktree_paths: Vec<String>) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        ProcessRunner::new("git")
+            .args(["worktree", "repair"])
+            .args(worktree_paths)
+            .cwd(&repo_path)
+            .label("git worktree repair")
+            .run()?;
+
+        Ok::<(), AppError>(())
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn get_changed_files(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<Vec<ChangedFile>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = open_repo(&worktree_path)?;
         
-        let mut files: Vec<ChangedFile> = Vec::new();
-        let mut path_to_idx: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        let base_branch_commit = find_base_commit(&repo, &resolve_base_branch(&app, &repo)?)?;
         
-        for delta in diff.deltas() {
-            let status = match delta.status() {
-                Delta::Added => "added",
-                Delta::Deleted => "deleted",
-                Delta::Modified => "modified",
-                Delta::Renamed => "renamed",
-                Delta::Copied => "copied",
-                Delta::Untracked => "untracked",
-                _ => "unknown",
-            };
-            
-            let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
-            let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
-            
-            if let Some(path) = new_path.clone().or(old_path.clone()) {
-                path_to_idx.insert(path.clone(), files.len());
-                files.push(ChangedFile {
-                    path,
-                    status: status.to_string(),
-                    old_path: if status == "renamed" { old_path } else { None },
-                    additions: 0,
-                    deletions: 0,
-                });
-            }
+        branch_changed_files(&repo, &base_branch_commit)
+    })
+    .await?
+}
+
+/// Files changed on HEAD since it forked from `base_commit`, with line counts.
+pub fn branch_changed_files(
+    repo: &Repository,
+    base_commit: &git2::Commit<'_>,
+) -> Result<Vec<ChangedFile>, AppError> {
+    let head_commit = repo.head()
+        .map_err(|e| AppError::Git(format!("Cannot get HEAD: {}", e.message())))?
+        .peel_to_commit()
+        .map_err(|e| AppError::Git(format!("Cannot get HEAD commit: {}", e.message())))?;
+
+    let merge_base_oid = repo
+        .merge_base(base_commit.id(), head_commit.id())
+        .map_err(|e| AppError::Git(format!("Cannot find merge base: {}", e.message())))?;
+    let merge_base_commit = repo
+        .find_commit(merge_base_oid)
+        .map_err(|e| AppError::Git(format!("Cannot get merge base commit: {}", e.message())))?;
+    let base_tree = merge_base_commit.tree()?;
+
+    let head_tree = head_commit.tree()?;
+
+    let mut diff_opts = DiffOptions::new();
+
+    let diff = repo
+        .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut diff_opts))?;
+
+    let mut files: Vec<ChangedFile> = Vec::new();
+    let mut path_to_idx: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+
+    for delta in diff.deltas() {
+        let status = match delta.status() {
+            Delta::Added => "added",
+            Delta::Deleted => "deleted",
+            Delta::Modified => "modified",
+            Delta::Renamed => "renamed",
+            Delta::Copied => "copied",
+            Delta::Untracked => "untracked",
+            _ => "unknown",
+        };
+
+        let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
+        let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
+
+        if let Some(path) = new_path.clone().or(old_path.clone()) {
+            path_to_idx.insert(path.clone(), files.len());
+            files.push(ChangedFile {
+                path,
+                status: status.to_string(),
+                old_path: if status == "renamed" { old_path } else { None },
+                additions: 0,
+                deletions: 0,
+            });
         }
-        
-        let _ = diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
-            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
-                let path_str = path.to_string_lossy().to_string();
-                if let Some(&idx) = path_to_idx.get(&path_str) {
-                    match line.origin() {
-                        '+' => files[idx].additions += 1,
-                        '-' => files[idx].deletions += 1,
-                        _ => {}
-                    }
+    }
+
+    let _ = diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+            let path_str = path.to_string_lossy().to_string();
+            if let Some(&idx) = path_to_idx.get(&path_str) {
+                match line.origin() {
+                    '+' => files[idx].additions += 1,
+                    '-' => files[idx].deletions += 1,
+                    _ => {}
                 }
             }
-            true
-        });
-        
-        Ok::<Vec<ChangedFile>, String>(files)
-    })
-    .await
-    .map_err(|e| e.to_string())?
+        }
+        true
+    });
+
+    Ok(files)
 }
 
 #[tauri::command]
-pub async fn get_file_diff(worktree_path: String, file_path: String) -> Result<FileDiffData, String> {
+pub async fn get_file_diff(
+    app: AppHandle,
+    worktree_path: String,
+    file_path: String,
+) -> Result<FileDiffData, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
-        let base_branch_commit = repo
-            .find_branch("origin/main", BranchType::Remote)
-            .or_else(|_| repo.find_branch("origin/master", BranchType::Remote))
-            .map_err(|e| format!("Cannot find origin/main or origin/master: {}", e.message()))?
-            .get()
-            .peel_to_commit()
-            .map_err(|e| format!("Cannot get base commit: {}", e.message()))?;
+        let base_branch_commit = find_base_commit(&repo, &resolve_base_branch(&app, &repo)?)?;
         
         let head_commit = repo.head()
-            .map_err(|e| format!("Cannot get HEAD: {}", e.message()))?
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD: {}", e.message())))?
             .peel_to_commit()
-            .map_err(|e| format!("Cannot get HEAD commit: {}", e.message()))?;
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD commit: {}", e.message())))?;
         
         let merge_base_oid = repo
             .merge_base(base_branch_commit.id(), head_commit.id())
-            .map_err(|e| format!("Cannot find merge base: {}", e.message()))?;
+            .map_err(|e| AppError::Git(format!("Cannot find merge base: {}", e.message())))?;
         let merge_base_commit = repo
             .find_commit(merge_base_oid)
-            .map_err(|e| format!("Cannot get merge base commit: {}", e.message()))?;
-        let base_tree = merge_base_commit.tree().map_err(|e| e.message().to_string())?;
+            .map_err(|e| AppError::Git(format!("Cannot get merge base commit: {}", e.message())))?;
+        let base_tree = merge_base_commit.tree()?;
         
-        let head_tree = head_commit.tree().map_err(|e| e.message().to_string())?;
+        let head_tree = head_commit.tree()?;
         
         let old_content = base_tree
             .get_path(std::path::Path::new(&file_path))
@@ -552,8 +1385,7 @@ pub async fn get_file_diff(worktree_path: String, file_path: String) -> Result<F
         diff_opts.pathspec(&file_path);
         
         let diff = repo
-            .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut diff_opts))
-            .map_err(|e| e.message().to_string())?;
+            .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut diff_opts))?;
         
         let mut patch = String::new();
         diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
@@ -565,42 +1397,52 @@ pub async fn get_file_diff(worktree_path: String, file_path: String) -> Result<F
                 patch.push_str(content);
             }
             true
-        }).map_err(|e| e.message().to_string())?;
+        })?;
         
-        Ok::<FileDiffData, String>(FileDiffData {
+        Ok::<FileDiffData, AppError>(FileDiffData {
             path: file_path,
             old_content,
             new_content,
             patch,
         })
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn get_uncommitted_files(worktree_path: String) -> Result<Vec<ChangedFile>, String> {
+pub async fn get_uncommitted_files(worktree_path: String) -> Result<Vec<ChangedFile>, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
         let head_commit = repo.head()
-            .map_err(|e| format!("Cannot get HEAD: {}", e.message()))?
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD: {}", e.message())))?
             .peel_to_commit()
-            .map_err(|e| format!("Cannot get HEAD commit: {}", e.message()))?;
-        let head_tree = head_commit.tree().map_err(|e| e.message().to_string())?;
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD commit: {}", e.message())))?;
+        let head_tree = head_commit.tree()?;
         
         let mut diff_opts = DiffOptions::new();
         diff_opts.include_untracked(true);
         diff_opts.recurse_untracked_dirs(true);
         
         let diff = repo
-            .diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_opts))
-            .map_err(|e| e.message().to_string())?;
+            .diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_opts))?;
         
+        let index = repo.index()?;
         let mut files: Vec<ChangedFile> = Vec::new();
         let mut path_to_idx: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
         
         for delta in diff.deltas() {
+            // Files outside the sparse-checkout set are absent, not deleted
+            if delta.status() == Delta::Deleted {
+                let skipped = delta
+                    .old_file()
+                    .path()
+                    .and_then(|p| p.to_str())
+                    .is_some_and(|p| is_skip_worktree(&index, p));
+                if skipped {
+                    continue;
+                }
+            }
             let status = match delta.status() {
                 Delta::Added => "added",
                 Delta::Deleted => "deleted",
@@ -640,22 +1482,21 @@ pub async fn get_uncommitted_files(worktree_path: String) -> Result<Vec<ChangedF
             true
         });
         
-        Ok::<Vec<ChangedFile>, String>(files)
+        Ok::<Vec<ChangedFile>, AppError>(files)
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn get_uncommitted_diff(worktree_path: String, file_path: String) -> Result<FileDiffData, String> {
+pub async fn get_uncommitted_diff(worktree_path: String, file_path: String) -> Result<FileDiffData, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
         let head_commit = repo.head()
-            .map_err(|e| format!("Cannot get HEAD: {}", e.message()))?
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD: {}", e.message())))?
             .peel_to_commit()
-            .map_err(|e| format!("Cannot get HEAD commit: {}", e.message()))?;
-        let head_tree = head_commit.tree().map_err(|e| e.message().to_string())?;
+            .map_err(|e| AppError::Git(format!("Cannot get HEAD commit: {}", e.message())))?;
+        let head_tree = head_commit.tree()?;
         
         let old_content = head_tree
             .get_path(std::path::Path::new(&file_path))
@@ -674,8 +1515,7 @@ pub async fn get_uncommitted_diff(worktree_path: String, file_path: String) -> R
         diff_opts.include_untracked(true);
         
         let diff = repo
-            .diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_opts))
-            .map_err(|e| e.message().to_string())?;
+            .diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut diff_opts))?;
         
         let mut patch = String::new();
         diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
@@ -687,7 +1527,7 @@ pub async fn get_uncommitted_diff(worktree_path: String, file_path: String) -> R
                 patch.push_str(content);
             }
             true
-        }).map_err(|e| e.message().to_string())?;
+        })?;
         
         let patch = if patch.is_empty() && is_new_file {
             if let Some(ref content) = new_content {
@@ -707,15 +1547,14 @@ pub async fn get_uncommitted_diff(worktree_path: String, file_path: String) -> R
             patch
         };
         
-        Ok::<FileDiffData, String>(FileDiffData {
+        Ok::<FileDiffData, AppError>(FileDiffData {
             path: file_path,
             old_content,
             new_content,
             patch,
         })
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
@@ -723,40 +1562,38 @@ pub async fn get_file_content(
     worktree_path: String,
     file_path: String,
     git_ref: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     tokio::task::spawn_blocking(move || {
         if let Some(ref_name) = git_ref {
-            let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+            let repo = open_repo(&worktree_path)?;
             
             let reference = repo
                 .find_branch(&ref_name, BranchType::Remote)
                 .or_else(|_| repo.find_branch(&ref_name, BranchType::Local))
-                .map_err(|e| format!("Cannot find ref {}: {}", ref_name, e.message()))?;
+                .map_err(|e| {
+                    AppError::NotFound(format!("Cannot find ref {}: {}", ref_name, e.message()))
+                })?;
             
             let commit = reference
                 .get()
-                .peel_to_commit()
-                .map_err(|e| e.message().to_string())?;
+                .peel_to_commit()?;
             
-            let tree = commit.tree().map_err(|e| e.message().to_string())?;
+            let tree = commit.tree()?;
             
             let entry = tree
-                .get_path(std::path::Path::new(&file_path))
-                .map_err(|e| e.message().to_string())?;
+                .get_path(std::path::Path::new(&file_path))?;
             
             let blob = repo
-                .find_blob(entry.id())
-                .map_err(|e| e.message().to_string())?;
+                .find_blob(entry.id())?;
             
             String::from_utf8(blob.content().to_vec())
-                .map_err(|_| "File is not valid UTF-8".to_string())
+                .map_err(|_| AppError::InvalidInput("File is not valid UTF-8".to_string()))
         } else {
             let full_path = PathBuf::from(&worktree_path).join(&file_path);
-            std::fs::read_to_string(&full_path).map_err(|e| e.to_string())
+            std::fs::read_to_string(&full_path).map_err(AppError::from)
         }
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -766,10 +1603,32 @@ pub struct GitStatusFile {
     pub staged: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmoduleState {
+    Uninitialized,
+    /// Checked out at a different commit than the superproject records
+    ModifiedCommit,
+    /// Has uncommitted or untracked changes of its own
+    Dirty,
+    Clean,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmoduleStatusEntry {
+    pub name: String,
+    pub path: String,
+    pub state: SubmoduleState,
+    pub staged: bool,
+    pub head_id: Option<String>,
+    pub workdir_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitStatus {
     pub staged: Vec<GitStatusFile>,
     pub unstaged: Vec<GitStatusFile>,
+    pub submodules: Vec<SubmoduleStatusEntry>,
     pub branch: Option<String>,
     pub upstream_branch: Option<String>,
     pub ahead: usize,
@@ -777,13 +1636,18 @@ pub struct GitStatus {
 }
 
 #[tauri::command]
-pub async fn get_git_status(worktree_path: String) -> Result<GitStatus, String> {
+pub async fn get_git_status(worktree_path: String) -> Result<GitStatus, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
         let mut staged: Vec<GitStatusFile> = Vec::new();
         let mut unstaged: Vec<GitStatusFile> = Vec::new();
         
+        let index = repo.index()?;
+        let submodules = get_submodule_statuses(&repo);
+        let submodule_paths: std::collections::HashSet<&str> =
+            submodules.iter().map(|s| s.path.as_str()).collect();
+        
         // Get status
         let statuses = repo
             .statuses(Some(
@@ -791,8 +1655,7 @@ pub async fn get_git_status(worktree_path: String) -> Result<GitStatus, String>
                     .include_untracked(true)
                     .recurse_untracked_dirs(true)
                     .include_ignored(false),
-            ))
-            .map_err(|e| e.message().to_string())?;
+            ))?;
         
         for entry in statuses.iter() {
             // Skip entries with invalid UTF-8 paths
@@ -800,7 +1663,15 @@ pub async fn get_git_status(worktree_path: String) -> Result<GitStatus, String>
                 Some(p) if !p.is_empty() => p.to_string(),
                 _ => continue,
             };
+            // Submodules are reported separately with their own state
+            if submodule_paths.contains(path.trim_end_matches('/')) {
+                continue;
+            }
             let status = entry.status();
+            // Files outside the sparse-checkout set are absent, not deleted
+            if status == git2::Status::WT_DELETED && is_skip_worktree(&index, &path) {
+                continue;
+            }
             
             // Check staged changes (index)
             if status.intersects(
@@ -858,17 +1729,63 @@ pub async fn get_git_status(worktree_path: String) -> Result<GitStatus, String>
             .map(|(name, ahead, behind)| (Some(name), ahead, behind))
             .unwrap_or((None, 0, 0));
         
-        Ok::<GitStatus, String>(GitStatus {
+        Ok::<GitStatus, AppError>(GitStatus {
             staged,
             unstaged,
+            submodules,
             branch,
             upstream_branch,
             ahead,
             behind,
         })
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
+}
+
+fn get_submodule_statuses(repo: &Repository) -> Vec<SubmoduleStatusEntry> {
+    let submodules = match repo.submodules() {
+        Ok(submodules) => submodules,
+        Err(_) => return Vec::new(),
+    };
+
+    submodules
+        .iter()
+        .filter_map(|sm| {
+            let name = sm.name()?.to_string();
+            let status = repo
+                .submodule_status(&name, git2::SubmoduleIgnore::None)
+                .ok()?;
+
+            let state = if status.contains(git2::SubmoduleStatus::WD_UNINITIALIZED) {
+                SubmoduleState::Uninitialized
+            } else if status.intersects(
+                git2::SubmoduleStatus::WD_MODIFIED | git2::SubmoduleStatus::INDEX_MODIFIED,
+            ) {
+                SubmoduleState::ModifiedCommit
+            } else if status.intersects(
+                git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                    | git2::SubmoduleStatus::WD_WD_MODIFIED
+                    | git2::SubmoduleStatus::WD_UNTRACKED,
+            ) {
+                SubmoduleState::Dirty
+            } else {
+                SubmoduleState::Clean
+            };
+
+            Some(SubmoduleStatusEntry {
+                name,
+                path: sm.path().to_string_lossy().to_string(),
+                state,
+                staged: status.intersects(
+                    git2::SubmoduleStatus::INDEX_ADDED
+                        | git2::SubmoduleStatus::INDEX_DELETED
+                        | git2::SubmoduleStatus::INDEX_MODIFIED,
+                ),
+                head_id: sm.head_id().map(|id| id.to_string()),
+                workdir_id: sm.workdir_id().map(|id| id.to_string()),
+            })
+        })
+        .collect()
 }
 
 fn get_upstream_info(repo: &Repository) -> Option<(String, usize, usize)> {
@@ -886,10 +1803,10 @@ fn get_upstream_info(repo: &Repository) -> Option<(String, usize, usize)> {
 }
 
 #[tauri::command]
-pub async fn git_stage_files(worktree_path: String, files: Vec<String>) -> Result<(), String> {
+pub async fn git_stage_files(worktree_path: String, files: Vec<String>) -> Result<(), AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
-        let mut index = repo.index().map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
+        let mut index = repo.index()?;
         
         for file in files {
             // Check if file exists - if not, it's a deletion
@@ -897,38 +1814,41 @@ pub async fn git_stage_files(worktree_path: String, files: Vec<String>) -> Resul
             if full_path.exists() {
                 index
                     .add_path(std::path::Path::new(&file))
-                    .map_err(|e| format!("Failed to stage {}: {}", file, e.message()))?;
+                    .map_err(|e| {
+                        AppError::Git(format!("Failed to stage {}: {}", file, e.message()))
+                    })?;
             } else {
                 index
                     .remove_path(std::path::Path::new(&file))
-                    .map_err(|e| format!("Failed to stage deletion {}: {}", file, e.message()))?;
+                    .map_err(|e| {
+                        AppError::Git(format!("Failed to stage deletion {}: {}", file, e.message()))
+                    })?;
             }
         }
         
-        index.write().map_err(|e| e.message().to_string())?;
+        index.write()?;
         
-        Ok::<(), String>(())
+        Ok::<(), AppError>(())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn git_unstage_files(worktree_path: String, files: Vec<String>) -> Result<(), String> {
+pub async fn git_unstage_files(worktree_path: String, files: Vec<String>) -> Result<(), AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
         // Try to get HEAD tree, but handle unborn branch (no commits yet)
         let head_tree = match repo.head() {
             Ok(head) => {
-                let head_commit = head.peel_to_commit().map_err(|e| e.message().to_string())?;
-                Some(head_commit.tree().map_err(|e| e.message().to_string())?)
+                let head_commit = head.peel_to_commit()?;
+                Some(head_commit.tree()?)
             }
             Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
-            Err(e) => return Err(e.message().to_string()),
+            Err(e) => return Err(e.into()),
         };
         
-        let mut index = repo.index().map_err(|e| e.message().to_string())?;
+        let mut index = repo.index()?;
         
         for file in files {
             let path = std::path::Path::new(&file);
@@ -938,7 +1858,12 @@ pub async fn git_unstage_files(worktree_path: String, files: Vec<String>) -> Res
             
             if let Some(entry) = entry_in_head {
                 // File exists in HEAD, restore it from HEAD
-                let blob = repo.find_blob(entry.id()).map_err(|e| e.message().to_string())?;
+                let blob = repo.find_blob(entry.id())?;
+                // Keeps the skip-worktree bit of sparse-excluded files
+                let flags_extended = index
+                    .get_path(path, 0)
+                    .map(|existing| existing.flags_extended)
+                    .unwrap_or(0);
                 index
                     .add(&git2::IndexEntry {
                         ctime: git2::IndexTime::new(0, 0),
@@ -951,41 +1876,44 @@ pub async fn git_unstage_files(worktree_path: String, files: Vec<String>) -> Res
                         file_size: blob.size() as u32,
                         id: entry.id(),
                         flags: 0,
-                        flags_extended: 0,
+                        flags_extended,
                         path: file.as_bytes().to_vec(),
                     })
-                    .map_err(|e| format!("Failed to unstage {}: {}", file, e.message()))?;
+                    .map_err(|e| {
+                        AppError::Git(format!("Failed to unstage {}: {}", file, e.message()))
+                    })?;
             } else {
                 // File doesn't exist in HEAD (or no HEAD exists), remove from index
                 index
                     .remove_path(path)
-                    .map_err(|e| format!("Failed to unstage {}: {}", file, e.message()))?;
+                    .map_err(|e| {
+                        AppError::Git(format!("Failed to unstage {}: {}", file, e.message()))
+                    })?;
             }
         }
         
-        index.write().map_err(|e| e.message().to_string())?;
+        index.write()?;
         
-        Ok::<(), String>(())
+        Ok::<(), AppError>(())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn git_commit(worktree_path: String, message: String) -> Result<String, String> {
+pub async fn git_commit(worktree_path: String, message: String) -> Result<String, AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
         
-        let mut index = repo.index().map_err(|e| e.message().to_string())?;
-        let tree_oid = index.write_tree().map_err(|e| e.message().to_string())?;
-        let tree = repo.find_tree(tree_oid).map_err(|e| e.message().to_string())?;
+        let mut index = repo.index()?;
+        let tree_oid = index.write_tree()?;
+        let tree = repo.find_tree(tree_oid)?;
         
-        let signature = repo.signature().map_err(|e| e.message().to_string())?;
+        let signature = repo.signature()?;
         
         // Handle initial commit (unborn HEAD) vs normal commit
         let commit_oid = match repo.head() {
             Ok(head) => {
-                let parent_commit = head.peel_to_commit().map_err(|e| e.message().to_string())?;
+                let parent_commit = head.peel_to_commit()?;
                 repo.commit(
                     Some("HEAD"),
                     &signature,
@@ -993,8 +1921,7 @@ pub async fn git_commit(worktree_path: String, message: String) -> Result<String
                     &message,
                     &tree,
                     &[&parent_commit],
-                )
-                .map_err(|e| e.message().to_string())?
+                )?
             }
             Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
                 // Initial commit - no parent
@@ -1005,165 +1932,69 @@ pub async fn git_commit(worktree_path: String, message: String) -> Result<String
                     &message,
                     &tree,
                     &[],
-                )
-                .map_err(|e| e.message().to_string())?
+                )?
             }
-            Err(e) => return Err(e.message().to_string()),
+            Err(e) => return Err(e.into()),
         };
         
-        Ok::<String, String>(commit_oid.to_string())
+        Ok::<String, AppError>(commit_oid.to_string())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
+/// Pushes the current branch. With an `operation_id` the push can be cancelled and
+/// its progress is streamed as `process-output` events.
 #[tauri::command]
-pub async fn git_push(worktree_path: String) -> Result<(), String> {
+pub async fn git_push(
+    app: AppHandle,
+    worktree_path: String,
+    operation_id: Option<String>,
+) -> Result<(), AppError> {
     tokio::task::spawn_blocking(move || {
-        let output = Command::new("git")
-            .args(["push"])
-            .current_dir(&worktree_path)
-            .output()
-            .map_err(|e| format!("Failed to run git push: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("git push failed: {}", stderr));
-        }
-
-        Ok::<(), String>(())
+        ProcessRunner::new("git")
+            .args(["push", "--progress"])
+            .cwd(&worktree_path)
+            .timeout(PUSH_TIMEOUT)
+            .operation(operation_id.as_deref())
+            .emit_output(&app)
+            .run()?;
+
+        Ok::<(), AppError>(())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn git_stage_all(worktree_path: String) -> Result<(), String> {
+pub async fn git_stage_all(worktree_path: String) -> Result<(), AppError> {
     tokio::task::spawn_blocking(move || {
-        let repo = Repository::open(&worktree_path).map_err(|e| e.message().to_string())?;
-        let mut index = repo.index().map_err(|e| e.message().to_string())?;
+        let repo = open_repo(&worktree_path)?;
+        let mut index = repo.index()?;
+        // Sparse-excluded files look deleted; staging them would delete them in the next commit
+        let snapshot = git2::Index::open(&repo.path().join("index"))?;
+        let mut skip_sparse = |path: &std::path::Path, _: &[u8]| -> i32 {
+            i32::from(is_skip_worktree(&snapshot, &path.to_string_lossy()))
+        };
         
         index
-            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
-            .map_err(|e| e.message().to_string())?;
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, Some(&mut skip_sparse))?;
         
         // Also handle deletions
         index
-            .update_all(["*"].iter(), None)
-            .map_err(|e| e.message().to_string())?;
+            .update_all(["*"].iter(), Some(&mut skip_sparse))?;
         
-        index.write().map_err(|e| e.message().to_string())?;
+        index.write()?;
         
-        Ok::<(), String>(())
-    })
-    .await
-    .map_err(|e| e.to_string())?
-}
-
-#[tauri::command]
-pub async fn git_unstage_all(worktree_path: String) -> Result<(), String> {
-    tokio::task::spawn_blocking(move || {
-        let output = Command::new("git")
-            .args(["reset"])
-            .current_dir(&worktree_path)
-            .output()
-            .map_err(|e| format!("Failed to run git reset: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("git reset failed: {}", stderr));
-        }
-
-        Ok::<(), String>(())
+        Ok::<(), AppError>(())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
 
 #[tauri::command]
-pub async fn generate_commit_message(
-    worktree_path: String,
-    agent: String,
-) -> Result<String, String> {
-    let agent_cmd = find_cli_tool(&agent)?;
-
+pub async fn git_unstage_all(worktree_path: String) -> Result<(), AppError> {
     tokio::task::spawn_blocking(move || {
-        let prompt = "Look at my staged changes (use git diff --cached) and generate a concise commit message. Return ONLY the commit message wrapped in XML tags like <commit_message>your message here</commit_message>. No other text.";
-
-        let output = match agent.as_str() {
-            "opencode" => {
-                Command::new(&agent_cmd)
-                    .args(["run", prompt])
-                    .current_dir(&worktree_path)
-                    .output()
-                    .map_err(|e| format!("Failed to run {}: {}", agent, e))?
-            }
-            "claude" => {
-                Command::new(&agent_cmd)
-                    .args([
-                        "-p", prompt,
-                        "--allowedTools", "Bash(git diff:*),Bash(git status:*)"
-                    ])
-                    .current_dir(&worktree_path)
-                    .output()
-                    .map_err(|e| format!("Failed to run {}: {}", agent, e))?
-            }
-            "aider" => {
-                Command::new(&agent_cmd)
-                    .args(["--message", prompt, "--yes"])
-                    .current_dir(&worktree_path)
-                    .output()
-                    .map_err(|e| format!("Failed to run {}: {}", agent, e))?
-            }
-            _ => {
-                Command::new(&agent_cmd)
-                    .args(["run", prompt])
-                    .current_dir(&worktree_path)
-                    .output()
-                    .map_err(|e| format!("Failed to run {}: {}", agent, e))?
-            }
-        };
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("{} failed: {}", agent, stderr));
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-
-        // Primary: XML tags (most reliable)
-        let xml_re = Regex::new(r"(?s)<commit_message>\s*(.*?)\s*</commit_message>").map_err(|e| e.to_string())?;
-        if let Some(captures) = xml_re.captures(&stdout) {
-            if let Some(message) = captures.get(1) {
-                let msg = message.as_str().trim();
-                if !msg.is_empty() {
-                    return Ok(msg.to_string());
-                }
-            }
-        }
-
-        // Fallback: square brackets (legacy compatibility)
-        let bracket_re = Regex::new(r"\[([^\]]+)\]").map_err(|e| e.to_string())?;
-        if let Some(captures) = bracket_re.captures(&stdout) {
-            if let Some(message) = captures.get(1) {
-                let msg = message.as_str().trim();
-                if !msg.is_empty() {
-                    return Ok(msg.to_string());
-                }
-            }
-        }
-
-        // Last resort: last non-empty line
-        let lines: Vec<&str> = stdout.lines()
-            .filter(|l| !l.trim().is_empty())
-            .collect();
-
-        if let Some(last_line) = lines.last() {
-            return Ok(last_line.trim().to_string());
-        }
+        run_git(&worktree_path, &["reset"])?;
 
-        Err("Could not extract commit message from AI response".to_string())
+        Ok::<(), AppError>(())
     })
-    .await
-    .map_err(|e| e.to_string())?
+    .await?
 }
@@ -1,6 +1,16 @@
 use super::cli_tools::find_cli_tool;
+use super::error::AppError;
+use super::forge::{remote_repo, Forge};
+use super::github_api::{forget_tokens, token_for, GitHubApi, DEFAULT_HOST};
+use super::runner::{ProcessOutput, ProcessRunner};
+use super::storage::{load_json, save_json};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// `cubic review` runs a full AI review and can take minutes
+const CUBIC_REVIEW_TIMEOUT: Duration = Duration::from_secs(900);
+const SETTINGS_FILE: &str = "github.json";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PRStatus {
@@ -73,221 +83,159 @@ fn compute_checks_status(checks: &[GhStatusCheck]) -> Option<String> {
     }
 }
 
-#[tauri::command]
-pub fn check_gh_cli() -> Result<bool, String> {
-    let gh_path = match find_cli_tool("gh") {
-        Ok(path) => path,
-        Err(_) => return Ok(false),
-    };
-    match Command::new(&gh_path).arg("--version").output() {
-        Ok(output) => Ok(output.status.success()),
-        Err(_) => Ok(false),
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PRHead {
+    pub head_ref_name: String,
+    #[serde(default)]
+    pub is_cross_repository: bool,
 }
 
-#[tauri::command]
-pub fn check_gh_auth() -> Result<String, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let output = Command::new(&gh_path)
-        .args(["auth", "status"])
-        .output()
-        .map_err(|e| format!("Failed to run gh auth status: {}", e))?;
-
-    let combined = format!(
-        "{}{}",
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
-    );
-    
-    if combined.contains("Logged in to") {
-        for line in combined.lines() {
-            if line.contains("account") {
-                if let Some(start) = line.find("account ") {
-                    let rest = &line[start + 8..];
-                    if let Some(end) = rest.find(' ') {
-                        return Ok(rest[..end].to_string());
-                    }
-                    return Ok(rest.trim().to_string());
-                }
-            }
-        }
-        Ok("authenticated".to_string())
-    } else {
-        Err("Not logged in to GitHub".to_string())
-    }
+pub struct NewPullRequest {
+    pub title: String,
+    pub body: String,
+    pub base: String,
+    pub draft: bool,
 }
 
-const PR_JSON_FIELDS: &str = "number,title,url,state,isDraft,mergedAt,reviewDecision,statusCheckRollup,additions,deletions,headRefName";
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHubBackendKind {
+    /// The HTTP API when a token can be found, the gh CLI otherwise
+    #[default]
+    Auto,
+    Cli,
+    Native,
+}
 
-#[tauri::command]
-pub async fn get_pr_for_branch(
-    repo_path: String,
-    branch: String,
-) -> Result<Option<PRStatus>, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let output = Command::new(&gh_path)
-        .args([
-            "pr", "list",
-            "--head", &branch,
-            "--state", "all",
-            "--limit", "1",
-            "--json", PR_JSON_FIELDS,
-        ])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh command failed: {}", stderr));
-    }
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct GitHubBackendSettings {
+    pub backend: GitHubBackendKind,
+}
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
+fn load_settings(app: &AppHandle) -> GitHubBackendSettings {
+    load_json(app, SETTINGS_FILE).unwrap_or_default()
+}
 
-    let prs: Vec<GhPRResponse> = serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+/// The GitHub backend configured in the settings. With `Auto`, the HTTP API is
+/// used when there's a token for `host`.
+pub fn backend_for(app: &AppHandle, host: &str) -> Box<dyn Forge> {
+    match load_settings(app).backend {
+        GitHubBackendKind::Cli => Box::new(GhCli),
+        GitHubBackendKind::Native => Box::new(GitHubApi),
+        GitHubBackendKind::Auto => match token_for(host) {
+            Some(_) => Box::new(GitHubApi),
+            None => Box::new(GhCli),
+        },
+    }
+}
 
-    Ok(prs.into_iter().next().map(|pr| PRStatus {
-        number: pr.number,
-        title: pr.title,
-        url: pr.url,
-        state: pr.state.to_lowercase(),
-        merged: pr.merged_at.is_some(),
-        draft: pr.is_draft,
-        review_decision: pr.review_decision.filter(|s| !s.is_empty()),
-        checks_status: compute_checks_status(&pr.status_check_rollup),
-        additions: pr.additions,
-        deletions: pr.deletions,
-        head_branch: pr.head_ref_name,
-    }))
+/// The `gh` executable, or `GhNotInstalled`.
+fn gh_path() -> Result<String, AppError> {
+    find_cli_tool("gh").map_err(|_| AppError::GhNotInstalled)
 }
 
-#[derive(Debug, Deserialize)]
-pub struct RepoWithBranches {
-    pub repo_path: String,
-    pub branches: Vec<String>,
+/// Turns a failed `gh` call into `GhNotAuthenticated` when that's the reason.
+fn gh_error(stderr: String) -> AppError {
+    if stderr.contains("gh auth login") || stderr.contains("not logged in") {
+        return AppError::GhNotAuthenticated;
+    }
+    AppError::CommandFailed {
+        command: "gh command".to_string(),
+        stderr,
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct RepoPRStatuses {
-    pub repo_path: String,
-    pub statuses: Vec<PRStatus>,
+fn gh_command(gh_path: &str, repo_path: &str, args: &[&str]) -> ProcessRunner<'static> {
+    ProcessRunner::new(gh_path)
+        .args(args)
+        .cwd(repo_path)
+        .label("gh command")
 }
 
-#[tauri::command]
-pub async fn get_all_prs_for_repos(repos: Vec<RepoWithBranches>) -> Result<Vec<RepoPRStatuses>, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let mut results = Vec::new();
-    
-    for repo in repos {
-        let mut statuses = Vec::new();
-        
-        for branch in &repo.branches {
-            let output = Command::new(&gh_path)
-                .args([
-                    "pr", "list",
-                    "--head", branch,
-                    "--state", "all",
-                    "--limit", "1",
-                    "--json", PR_JSON_FIELDS,
-                ])
-                .current_dir(&repo.repo_path)
-                .output();
-
-            if let Ok(out) = output {
-                if out.status.success() {
-                    let stdout = String::from_utf8(out.stdout).unwrap_or_default();
-                    let prs: Vec<GhPRResponse> = serde_json::from_str(&stdout).unwrap_or_default();
-                    if let Some(pr) = prs.into_iter().next() {
-                        statuses.push(PRStatus {
-                            number: pr.number,
-                            title: pr.title,
-                            url: pr.url,
-                            state: pr.state.to_lowercase(),
-                            merged: pr.merged_at.is_some(),
-                            draft: pr.is_draft,
-                            review_decision: pr.review_decision.filter(|s| !s.is_empty()),
-                            checks_status: compute_checks_status(&pr.status_check_rollup),
-                            additions: pr.additions,
-                            deletions: pr.deletions,
-                            head_branch: pr.head_ref_name,
-                        });
-                    }
-                }
-            }
-        }
+/// Runs `gh` in `repo_path` and returns its stdout.
+fn run_gh(repo_path: &str, args: &[&str]) -> Result<String, AppError> {
+    let output = gh_command(&gh_path()?, repo_path, args).output()?;
+    if !output.success() {
+        return Err(gh_error(output.stderr));
+    }
+    Ok(output.stdout)
+}
 
-        results.push(RepoPRStatuses { repo_path: repo.repo_path, statuses });
+fn gh_installed() -> bool {
+    let Ok(gh_path) = gh_path() else {
+        return false;
+    };
+    match ProcessRunner::new(&gh_path).arg("--version").output() {
+        Ok(output) => output.success(),
+        Err(_) => false,
     }
+}
 
-    Ok(results)
+/// The GitHub host `repo_path`'s origin points at, github.com without a repository.
+fn github_host(repo_path: Option<&str>) -> String {
+    repo_path
+        .and_then(|path| remote_repo(path).ok())
+        .map(|remote| remote.host)
+        .unwrap_or_else(|| DEFAULT_HOST.to_string())
 }
 
+/// Whether GitHub features are usable with the configured backend: the gh CLI is
+/// installed, or a token for the HTTP API was found for the repository's host.
 #[tauri::command]
-pub async fn get_pr_status(
-    repo_path: String,
-    pr_number: u64,
-) -> Result<PRStatus, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let pr_ref = format!("{}", pr_number);
-    
-    let output = Command::new(&gh_path)
-        .args([
-            "pr", "view", &pr_ref,
-            "--json", PR_JSON_FIELDS,
-        ])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh command failed: {}", stderr));
-    }
+pub async fn check_gh_cli(app: AppHandle, repo_path: Option<String>) -> Result<bool, AppError> {
+    let available = tokio::task::spawn_blocking(move || {
+        let host = github_host(repo_path.as_deref());
+        let has_token = || token_for(&host).is_some();
+        match load_settings(&app).backend {
+            GitHubBackendKind::Cli => gh_installed(),
+            GitHubBackendKind::Native => has_token(),
+            GitHubBackendKind::Auto => has_token() || gh_installed(),
+        }
+    })
+    .await?;
+    Ok(available)
+}
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
-
-    let pr: GhPRResponse = serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    Ok(PRStatus {
-        number: pr.number,
-        title: pr.title,
-        url: pr.url,
-        state: pr.state.to_lowercase(),
-        merged: pr.merged_at.is_some(),
-        draft: pr.is_draft,
-        review_decision: pr.review_decision.filter(|s| !s.is_empty()),
-        checks_status: compute_checks_status(&pr.status_check_rollup),
-        additions: pr.additions,
-        deletions: pr.deletions,
-        head_branch: pr.head_ref_name,
+#[tauri::command]
+pub async fn check_gh_auth(app: AppHandle, repo_path: Option<String>) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let host = github_host(repo_path.as_deref());
+        backend_for(&app, &host).current_user(&host)
     })
+    .await?
 }
 
 #[tauri::command]
-pub async fn get_repo_from_remote(repo_path: String) -> Result<Option<String>, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let output = Command::new(&gh_path)
-        .args(["repo", "view", "--json", "nameWithOwner", "-q", ".nameWithOwner"])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
-
-    if output.status.success() {
-        let name = String::from_utf8(output.stdout)
-            .map_err(|e| format!("Invalid UTF-8: {}", e))?
-            .trim()
-            .to_string();
-        if name.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(name))
+pub fn get_github_settings(app: AppHandle) -> GitHubBackendSettings {
+    load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_github_settings(app: AppHandle, settings: GitHubBackendSettings) -> Result<(), AppError> {
+    // Look tokens up again, the user may have just logged in
+    forget_tokens();
+    save_json(&app, SETTINGS_FILE, &settings)
+}
+
+const PR_JSON_FIELDS: &str = "number,title,url,state,isDraft,mergedAt,reviewDecision,statusCheckRollup,additions,deletions,headRefName";
+
+impl From<GhPRResponse> for PRStatus {
+    fn from(pr: GhPRResponse) -> Self {
+        PRStatus {
+            number: pr.number,
+            title: pr.title,
+            url: pr.url,
+            state: pr.state.to_lowercase(),
+            merged: pr.merged_at.is_some(),
+            draft: pr.is_draft,
+            review_decision: pr.review_decision.filter(|s| !s.is_empty()),
+            checks_status: compute_checks_status(&pr.status_check_rollup),
+            additions: pr.additions,
+            deletions: pr.deletions,
+            head_branch: pr.head_ref_name,
         }
-    } else {
-        Ok(None)
     }
 }
 
@@ -320,59 +268,34 @@ struct GhCheckRun {
     completed_at: Option<String>,
 }
 
-#[tauri::command]
-pub async fn get_pr_checks(repo_path: String, pr_number: u64) -> Result<PRChecksResult, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let pr_ref = format!("{}", pr_number);
-    
-    let output = Command::new(&gh_path)
-        .args([
-            "pr", "checks", &pr_ref,
-            "--json", "name,state,description,link,startedAt,completedAt",
-        ])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("no checks") {
-            return Ok(PRChecksResult {
-                checks: vec![],
-                overall_status: "none".to_string(),
-            });
-        }
-        return Err(format!("gh command failed: {}", stderr));
+/// Builds a check from a `gh pr checks` style state (SUCCESS, FAILURE, IN_PROGRESS, ...).
+pub fn pr_check(
+    name: String,
+    state: &str,
+    url: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+) -> PRCheck {
+    let conclusion = match state {
+        "SUCCESS" => Some("success".to_string()),
+        "FAILURE" | "ERROR" => Some("failure".to_string()),
+        "CANCELLED" => Some("cancelled".to_string()),
+        "PENDING" | "QUEUED" | "IN_PROGRESS" => None,
+        _ => None,
+    };
+    let status = if conclusion.is_some() { "completed".to_string() } else { "in_progress".to_string() };
+    PRCheck {
+        name,
+        status,
+        conclusion,
+        url,
+        started_at,
+        completed_at,
     }
+}
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
-
-    let gh_checks: Vec<GhCheckRun> = serde_json::from_str(&stdout).unwrap_or_default();
-
-    let checks: Vec<PRCheck> = gh_checks
-        .into_iter()
-        .map(|c| {
-            let conclusion = match c.state.as_str() {
-                "SUCCESS" => Some("success".to_string()),
-                "FAILURE" | "ERROR" => Some("failure".to_string()),
-                "CANCELLED" => Some("cancelled".to_string()),
-                "PENDING" | "QUEUED" | "IN_PROGRESS" => None,
-                _ => None,
-            };
-            let status = if conclusion.is_some() { "completed".to_string() } else { "in_progress".to_string() };
-            PRCheck {
-                name: c.name,
-                status,
-                conclusion,
-                url: c.link,
-                started_at: c.started_at,
-                completed_at: c.completed_at,
-            }
-        })
-        .collect();
-
-    let overall_status = if checks.is_empty() {
+pub fn checks_overall_status(checks: &[PRCheck]) -> String {
+    if checks.is_empty() {
         "none".to_string()
     } else if checks.iter().any(|c| c.conclusion.as_deref() == Some("failure")) {
         "failure".to_string()
@@ -380,12 +303,7 @@ pub async fn get_pr_checks(repo_path: String, pr_number: u64) -> Result<PRChecks
         "pending".to_string()
     } else {
         "success".to_string()
-    };
-
-    Ok(PRChecksResult {
-        checks,
-        overall_status,
-    })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -435,7 +353,7 @@ struct GhReview {
 }
 
 #[derive(Debug, Deserialize)]
-struct RestApiReview {
+pub struct RestApiReview {
     id: u64,
     user: GhCommentAuthor,
     body: String,
@@ -444,7 +362,7 @@ struct RestApiReview {
 }
 
 #[derive(Debug, Deserialize)]
-struct GhReviewComment {
+pub struct GhReviewComment {
     user: GhCommentAuthor,
     body: String,
     created_at: String,
@@ -469,27 +387,79 @@ struct GhPRDetailedResponse {
     review_decision: Option<String>,
 }
 
-async fn fetch_reviews_rest_api(repo_path: &str, pr_number: u64) -> Result<Vec<RestApiReview>, String> {
-    let gh_path = find_cli_tool("gh")?;
+/// Combines issue comments, reviews and review thread comments into one timeline.
+pub fn merge_pr_comments(
+    mut all_comments: Vec<PRComment>,
+    rest_reviews: Vec<RestApiReview>,
+    review_comments: Vec<GhReviewComment>,
+) -> Vec<PRComment> {
+    eprintln!("DEBUG: Found {} reviews from REST API", rest_reviews.len());
+    for review in rest_reviews {
+        eprintln!("DEBUG: Review - id: {}, author: {}, state: {}, has_body: {}", 
+                  review.id, review.user.login, review.state, !review.body.is_empty());
+        all_comments.push(PRComment {
+            author: review.user.login,
+            body: review.body,
+            created_at: review.submitted_at.unwrap_or_default(),
+            comment_type: "review".to_string(),
+            state: Some(review.state),
+            path: None,
+            line: None,
+            review_id: Some(review.id.to_string()),
+        });
+    }
+
+    eprintln!("DEBUG: Fetched {} review comments", review_comments.len());
+    for rc in &review_comments {
+        eprintln!("DEBUG: Review comment - id: {}, author: {}, review_id: {:?}, path: {}", 
+                  rc.id, rc.user.login, rc.pull_request_review_id, rc.path);
+    }
+    for rc in review_comments {
+        all_comments.push(PRComment {
+            author: rc.user.login,
+            body: rc.body,
+            created_at: rc.created_at,
+            comment_type: "review_thread".to_string(),
+            state: None,
+            path: Some(rc.path),
+            line: rc.line.or(rc.original_line),
+            review_id: rc.pull_request_review_id.map(|id| id.to_string()),
+        });
+    }
+
+    all_comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    eprintln!("DEBUG: Final comment breakdown:");
+    eprintln!("  - Issue comments: {}", all_comments.iter().filter(|c| c.comment_type == "issue").count());
+    eprintln!("  - Reviews: {}", all_comments.iter().filter(|c| c.comment_type == "review").count());
+    eprintln!("  - Review threads: {}", all_comments.iter().filter(|c| c.comment_type == "review_thread").count());
+    eprintln!("DEBUG: Total comments being returned: {}", all_comments.len());
+
+    all_comments
+}
+
+fn fetch_reviews_rest_api(repo_path: &str, pr_number: u64) -> Result<Vec<RestApiReview>, AppError> {
+    let gh_path = gh_path()?;
     eprintln!("DEBUG: Fetching reviews via REST API for PR #{}", pr_number);
-    let output = Command::new(&gh_path)
-        .args([
+    let output = gh_command(
+        &gh_path,
+        repo_path,
+        &[
             "api",
             &format!("repos/{{owner}}/{{repo}}/pulls/{}/reviews", pr_number),
             "--paginate",
-        ])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| format!("Failed to fetch reviews: {}", e))?;
+        ],
+    )
+    .output()
+    .map_err(|e| format!("Failed to fetch reviews: {}", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.success() {
+        let stderr = &output.stderr;
         eprintln!("DEBUG: gh api reviews failed: {}", stderr);
         return Ok(Vec::new());
     }
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
+    let stdout = output.stdout;
 
     match serde_json::from_str::<Vec<RestApiReview>>(&stdout) {
         Ok(reviews) => {
@@ -503,27 +473,28 @@ async fn fetch_reviews_rest_api(repo_path: &str, pr_number: u64) -> Result<Vec<R
     }
 }
 
-async fn fetch_review_comments(repo_path: &str, pr_number: u64) -> Result<Vec<GhReviewComment>, String> {
-    let gh_path = find_cli_tool("gh")?;
+fn fetch_review_comments(repo_path: &str, pr_number: u64) -> Result<Vec<GhReviewComment>, AppError> {
+    let gh_path = gh_path()?;
     eprintln!("DEBUG: Fetching review comments for PR #{}", pr_number);
-    let output = Command::new(&gh_path)
-        .args([
+    let output = gh_command(
+        &gh_path,
+        repo_path,
+        &[
             "api",
             &format!("repos/{{owner}}/{{repo}}/pulls/{}/comments", pr_number),
             "--paginate",
-        ])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| format!("Failed to fetch review comments: {}", e))?;
+        ],
+    )
+    .output()
+    .map_err(|e| format!("Failed to fetch review comments: {}", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.success() {
+        let stderr = &output.stderr;
         eprintln!("DEBUG: gh api failed: {}", stderr);
         return Ok(Vec::new());
     }
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
+    let stdout = output.stdout;
 
     match serde_json::from_str::<Vec<GhReviewComment>>(&stdout) {
         Ok(comments) => {
@@ -537,148 +508,231 @@ async fn fetch_review_comments(repo_path: &str, pr_number: u64) -> Result<Vec<Gh
     }
 }
 
-#[tauri::command]
-pub async fn get_pr_details(repo_path: String, pr_number: u64) -> Result<PRDetailedInfo, String> {
-    let gh_path = find_cli_tool("gh")?;
-    let pr_ref = format!("{}", pr_number);
-    
-    let output = Command::new(&gh_path)
-        .args([
-            "pr", "view", &pr_ref,
-            "--json", "mergeStateStatus,mergeable,comments,reviews,reviewDecision",
-        ])
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh command failed: {}", stderr));
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreatePRResult {
+    pub number: u64,
+    pub url: String,
+}
+
+/// The `gh` CLI backend: one process per call, using gh's own login.
+pub struct GhCli;
+
+impl Forge for GhCli {
+    fn current_user(&self, host: &str) -> Result<String, AppError> {
+        let gh_path = gh_path()?;
+        let output = ProcessRunner::new(&gh_path)
+            .args(["auth", "status", "--hostname", host])
+            .label("gh auth status")
+            .output()?;
+
+        let combined = format!("{}{}", output.stdout, output.stderr);
+
+        if combined.contains("Logged in to") {
+            for line in combined.lines() {
+                if line.contains("account") {
+                    if let Some(start) = line.find("account ") {
+                        let rest = &line[start + 8..];
+                        if let Some(end) = rest.find(' ') {
+                            return Ok(rest[..end].to_string());
+                        }
+                        return Ok(rest.trim().to_string());
+                    }
+                }
+            }
+            Ok("authenticated".to_string())
+        } else {
+            Err(AppError::GhNotAuthenticated)
+        }
     }
 
-    let stdout = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?;
+    fn pr_for_branch(&self, repo_path: &str, branch: &str) -> Result<Option<PRStatus>, AppError> {
+        let stdout = run_gh(
+            repo_path,
+            &[
+                "pr", "list",
+                "--head", branch,
+                "--state", "all",
+                "--limit", "1",
+                "--json", PR_JSON_FIELDS,
+            ],
+        )?;
+
+        let prs: Vec<GhPRResponse> = serde_json::from_str(&stdout)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        Ok(prs.into_iter().next().map(PRStatus::from))
+    }
 
-    let pr: GhPRDetailedResponse = serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    fn pr_status(&self, repo_path: &str, number: u64) -> Result<PRStatus, AppError> {
+        let pr_ref = format!("{}", number);
 
-    let mut all_comments: Vec<PRComment> = pr.comments.into_iter().map(|c| PRComment {
-        author: c.author.login,
-        body: c.body,
-        created_at: c.created_at,
-        comment_type: "issue".to_string(),
-        state: None,
-        path: None,
-        line: None,
-        review_id: None,
-    }).collect();
+        let stdout = run_gh(
+            repo_path,
+            &[
+                "pr", "view", &pr_ref,
+                "--json", PR_JSON_FIELDS,
+            ],
+        )?;
 
-    let rest_reviews = fetch_reviews_rest_api(&repo_path, pr_number).await?;
-    eprintln!("DEBUG: Found {} reviews from REST API", rest_reviews.len());
-    for review in rest_reviews {
-        eprintln!("DEBUG: Review - id: {}, author: {}, state: {}, has_body: {}", 
-                  review.id, review.user.login, review.state, !review.body.is_empty());
-        all_comments.push(PRComment {
-            author: review.user.login,
-            body: review.body,
-            created_at: review.submitted_at.unwrap_or_default(),
-            comment_type: "review".to_string(),
-            state: Some(review.state),
-            path: None,
-            line: None,
-            review_id: Some(review.id.to_string()),
-        });
+        let pr: GhPRResponse = serde_json::from_str(&stdout)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        Ok(pr.into())
     }
 
-    let review_comments = fetch_review_comments(&repo_path, pr_number).await?;
-    eprintln!("DEBUG: Fetched {} review comments", review_comments.len());
-    for rc in &review_comments {
-        eprintln!("DEBUG: Review comment - id: {}, author: {}, review_id: {:?}, path: {}", 
-                  rc.id, rc.user.login, rc.pull_request_review_id, rc.path);
+    fn pr_head(&self, repo_path: &str, number: u64) -> Result<PRHead, AppError> {
+        let pr_ref = number.to_string();
+        let stdout = run_gh(
+            repo_path,
+            &["pr", "view", &pr_ref, "--json", "headRefName,isCrossRepository"],
+        )?;
+
+        Ok(serde_json::from_str(&stdout)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?)
     }
-    for rc in review_comments {
-        all_comments.push(PRComment {
-            author: rc.user.login,
-            body: rc.body,
-            created_at: rc.created_at,
-            comment_type: "review_thread".to_string(),
-            state: None,
-            path: Some(rc.path),
-            line: rc.line.or(rc.original_line),
-            review_id: rc.pull_request_review_id.map(|id| id.to_string()),
-        });
+
+    fn repo_name(&self, repo_path: &str) -> Result<Option<String>, AppError> {
+        let gh_path = gh_path()?;
+        let output = gh_command(
+            &gh_path,
+            repo_path,
+            &["repo", "view", "--json", "nameWithOwner", "-q", ".nameWithOwner"],
+        )
+        .output()?;
+
+        if output.success() {
+            let name = output.stdout.trim().to_string();
+            if name.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(name))
+            }
+        } else {
+            Ok(None)
+        }
     }
 
-    all_comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    fn pr_checks(&self, repo_path: &str, number: u64) -> Result<PRChecksResult, AppError> {
+        let gh_path = gh_path()?;
+        let pr_ref = format!("{}", number);
+
+        let output = gh_command(
+            &gh_path,
+            repo_path,
+            &[
+                "pr", "checks", &pr_ref,
+                "--json", "name,state,description,link,startedAt,completedAt",
+            ],
+        )
+        .output()?;
+
+        if !output.success() {
+            let stderr = &output.stderr;
+            if stderr.contains("no checks") {
+                return Ok(PRChecksResult {
+                    checks: vec![],
+                    overall_status: "none".to_string(),
+                });
+            }
+            return Err(gh_error(output.stderr));
+        }
 
-    eprintln!("DEBUG: Final comment breakdown:");
-    eprintln!("  - Issue comments: {}", all_comments.iter().filter(|c| c.comment_type == "issue").count());
-    eprintln!("  - Reviews: {}", all_comments.iter().filter(|c| c.comment_type == "review").count());
-    eprintln!("  - Review threads: {}", all_comments.iter().filter(|c| c.comment_type == "review_thread").count());
-    eprintln!("DEBUG: Total comments being returned: {}", all_comments.len());
+        let stdout = output.stdout;
 
-    Ok(PRDetailedInfo {
-        merge_state_status: pr.merge_state_status,
-        mergeable: pr.mergeable,
-        comments: all_comments,
-        review_decision: pr.review_decision,
-    })
-}
+        let gh_checks: Vec<GhCheckRun> = serde_json::from_str(&stdout).unwrap_or_default();
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct CreatePRResult {
-    pub number: u64,
-    pub url: String,
-}
+        let checks: Vec<PRCheck> = gh_checks
+            .into_iter()
+            .map(|c| pr_check(c.name, &c.state, c.link, c.started_at, c.completed_at))
+            .collect();
 
-#[tauri::command]
-pub async fn create_pr(
-    repo_path: String,
-    title: String,
-    body: Option<String>,
-    base: Option<String>,
-    draft: bool,
-) -> Result<CreatePRResult, String> {
-    let mut args = vec!["pr", "create", "--title", &title];
-    
-    let body_str = body.unwrap_or_default();
-    if !body_str.is_empty() {
-        args.push("--body");
-        args.push(&body_str);
-    }
-    
-    let base_str = base.unwrap_or_else(|| "main".to_string());
-    args.push("--base");
-    args.push(&base_str);
-    
-    if draft {
-        args.push("--draft");
+        Ok(PRChecksResult {
+            overall_status: checks_overall_status(&checks),
+            checks,
+        })
     }
 
-    let gh_path = find_cli_tool("gh")?;
-    let output = Command::new(&gh_path)
-        .args(&args)
-        .current_dir(&repo_path)
-        .output()
-        .map_err(|e| format!("Failed to run gh command: {}", e))?;
+    fn pr_details(&self, repo_path: &str, number: u64) -> Result<PRDetailedInfo, AppError> {
+        let pr_ref = format!("{}", number);
+
+        let stdout = run_gh(
+            repo_path,
+            &[
+                "pr", "view", &pr_ref,
+                "--json", "mergeStateStatus,mergeable,comments,reviews,reviewDecision",
+            ],
+        )?;
+
+        let pr: GhPRDetailedResponse = serde_json::from_str(&stdout)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let comments: Vec<PRComment> = pr.comments.into_iter().map(|c| PRComment {
+            author: c.author.login,
+            body: c.body,
+            created_at: c.created_at,
+            comment_type: "issue".to_string(),
+            state: None,
+            path: None,
+            line: None,
+            review_id: None,
+        }).collect();
+
+        let rest_reviews = fetch_reviews_rest_api(repo_path, number)?;
+        let review_comments = fetch_review_comments(repo_path, number)?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Failed to create PR: {}", stderr));
+        Ok(PRDetailedInfo {
+            merge_state_status: pr.merge_state_status,
+            mergeable: pr.mergeable,
+            comments: merge_pr_comments(comments, rest_reviews, review_comments),
+            review_decision: pr.review_decision,
+        })
     }
 
-    let url = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Invalid UTF-8 output: {}", e))?
-        .trim()
-        .to_string();
+    fn create_pr(
+        &self,
+        repo_path: &str,
+        pr: &NewPullRequest,
+        operation_id: Option<&str>,
+    ) -> Result<CreatePRResult, AppError> {
+        let mut args = vec!["pr", "create", "--title", &pr.title];
+
+        if !pr.body.is_empty() {
+            args.push("--body");
+            args.push(&pr.body);
+        }
+
+        args.push("--base");
+        args.push(&pr.base);
 
-    let number = url
-        .split('/')
-        .last()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(0);
+        if pr.draft {
+            args.push("--draft");
+        }
+
+        let gh_path = gh_path()?;
+        let output = gh_command(&gh_path, repo_path, &args)
+            .operation(operation_id)
+            .output()?;
+
+        if !output.success() {
+            return Err(match gh_error(output.stderr) {
+                AppError::CommandFailed { stderr, .. } => AppError::CommandFailed {
+                    command: "gh pr create".to_string(),
+                    stderr,
+                },
+                other => other,
+            });
+        }
 
-    Ok(CreatePRResult { number, url })
+        let url = output.stdout.trim().to_string();
+
+        let number = url
+            .split('/')
+            .last()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        Ok(CreatePRResult { number, url })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -688,20 +742,26 @@ pub struct CubicReviewResult {
     pub error: Option<String>,
 }
 
+/// Runs `cubic review`. With an `operation_id` the review can be cancelled and its
+/// output is streamed as `process-output` events.
 #[tauri::command]
-pub async fn run_cubic_review(repo_path: String) -> Result<CubicReviewResult, String> {
+pub async fn run_cubic_review(
+    app: AppHandle,
+    repo_path: String,
+    operation_id: Option<String>,
+) -> Result<CubicReviewResult, AppError> {
     let cubic_path = find_cli_tool("cubic")?;
-    let output = Command::new(&cubic_path)
+    let output = ProcessRunner::new(&cubic_path)
         .args(["review"])
-        .current_dir(&repo_path)
+        .cwd(&repo_path)
+        .timeout(CUBIC_REVIEW_TIMEOUT)
+        .operation(operation_id.as_deref())
+        .emit_output(&app)
         .output();
 
     match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-            
-            if out.status.success() {
+        Ok(ProcessOutput { code, stdout, stderr }) => {
+            if code == Some(0) {
                 Ok(CubicReviewResult {
                     success: true,
                     output: stdout,
@@ -715,8 +775,6 @@ pub async fn run_cubic_review(repo_path: String) -> Result<CubicReviewResult, St
                 })
             }
         }
-        Err(e) => {
-            Err(format!("Failed to run cubic: {}", e))
-        }
+        Err(e) => Err(e.into()),
     }
 }
@@ -0,0 +1,592 @@
+use base64::Engine;
+use parking_lot::Mutex;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::error::AppError;
+use super::forge::{
+    current_branch, host_config_value, http_client, next_page, remote_repo, request_error,
+    response_error, Forge, RemoteRepo,
+};
+use super::github::{
+    checks_overall_status, merge_pr_comments, pr_check, CreatePRResult, GhReviewComment,
+    NewPullRequest, PRChecksResult, PRComment, PRDetailedInfo, PRHead, PRStatus, RestApiReview,
+};
+use super::runner::{Operation, ProcessRunner};
+
+pub const DEFAULT_HOST: &str = "github.com";
+const API_VERSION: &str = "2022-11-28";
+const KEYCHAIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// Branches looked up per GraphQL query, to stay well below GitHub's node limits
+const BRANCHES_PER_QUERY: usize = 50;
+
+const PR_FIELDS: &str =
+    "number title url state isDraft mergedAt reviewDecision additions deletions headRefName \
+     commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }";
+const CHECKS_FIELDS: &str =
+    "commits(last: 1) { nodes { commit { statusCheckRollup { contexts(first: 100) { nodes { \
+     __typename \
+     ... on CheckRun { name status conclusion detailsUrl startedAt completedAt } \
+     ... on StatusContext { context state targetUrl createdAt } \
+     } } } } } }";
+const DETAILS_FIELDS: &str = "mergeStateStatus mergeable reviewDecision";
+
+/// Tokens by host; `None` records a lookup that found nothing
+static TOKENS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+
+/// Finds a token for `host` where gh would: the environment, gh's hosts.yml, then
+/// the system keychain, which is where `gh auth login` puts it by default.
+pub fn token_for(host: &str) -> Option<String> {
+    let tokens = TOKENS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(token) = tokens.lock().get(host) {
+        return token.clone();
+    }
+    let token = env_token(host)
+        .or_else(|| config_token(host))
+        .or_else(|| keychain_token(host));
+    tokens.lock().insert(host.to_string(), token.clone());
+    token
+}
+
+/// Drops cached lookups, e.g. after a token was rejected or the user logged in.
+pub fn forget_tokens() {
+    if let Some(tokens) = TOKENS.get() {
+        tokens.lock().clear();
+    }
+}
+
+fn env_token(host: &str) -> Option<String> {
+    let names: &[&str] = if host == DEFAULT_HOST {
+        &["GH_TOKEN", "GITHUB_TOKEN"]
+    } else {
+        &["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|token| !token.trim().is_empty())
+}
+
+/// `$GH_CONFIG_DIR`, `$XDG_CONFIG_HOME/gh`, or `~/.config/gh` (on macOS too).
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("gh"));
+    }
+    dirs::home_dir().map(|home| home.join(".config").join("gh"))
+}
+
+/// gh only writes `oauth_token` to hosts.yml when told to store tokens insecurely.
+fn config_token(host: &str) -> Option<String> {
+    let contents = std::fs::read_to_string(gh_config_dir()?.join("hosts.yml")).ok()?;
+    host_config_value(&contents, host, "oauth_token")
+}
+
+/// gh stores keychain tokens under the service `gh:<host>`.
+fn keychain_token(host: &str) -> Option<String> {
+    let service = format!("gh:{}", host);
+    let runner = if cfg!(target_os = "macos") {
+        ProcessRunner::new("security").args(["find-generic-password", "-s", &service, "-w"])
+    } else {
+        ProcessRunner::new("secret-tool").args(["lookup", "service", &service])
+    };
+    let secret = runner.timeout(KEYCHAIN_TIMEOUT).run().ok()?;
+    let secret = secret.trim();
+
+    // go-keyring, which gh uses, base64-encodes secrets on macOS
+    let token = match secret.strip_prefix("go-keyring-base64:") {
+        Some(encoded) => String::from_utf8(
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok()?,
+        )
+        .ok()?,
+        None => secret.to_string(),
+    };
+    (!token.is_empty()).then_some(token)
+}
+
+fn rest_base(host: &str) -> String {
+    if host == DEFAULT_HOST {
+        "https://api.github.com".to_string()
+    } else {
+        format!("https://{}/api/v3", host)
+    }
+}
+
+fn graphql_url(host: &str) -> String {
+    if host == DEFAULT_HOST {
+        "https://api.github.com/graphql".to_string()
+    } else {
+        format!("https://{}/api/graphql", host)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQLResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQLError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQLError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepositoryData<T> {
+    repository: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestData<T> {
+    pull_request: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLogin {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCommitNode<T> {
+    commit: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiRollupCommit {
+    status_check_rollup: Option<ApiRollupState>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRollupState {
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiPullRequest {
+    number: u64,
+    title: String,
+    url: String,
+    state: String,
+    #[serde(default)]
+    is_draft: bool,
+    merged_at: Option<String>,
+    review_decision: Option<String>,
+    #[serde(default)]
+    additions: u64,
+    #[serde(default)]
+    deletions: u64,
+    head_ref_name: String,
+    commits: Nodes<ApiCommitNode<ApiRollupCommit>>,
+}
+
+impl From<ApiPullRequest> for PRStatus {
+    fn from(pr: ApiPullRequest) -> Self {
+        // The rollup is SUCCESS, FAILURE, ERROR, PENDING or EXPECTED
+        let checks_status = pr
+            .commits
+            .nodes
+            .into_iter()
+            .next()
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(|rollup| match rollup.state.as_str() {
+                "SUCCESS" => "success".to_string(),
+                "FAILURE" | "ERROR" => "failure".to_string(),
+                _ => "pending".to_string(),
+            });
+        PRStatus {
+            number: pr.number,
+            title: pr.title,
+            url: pr.url,
+            state: pr.state.to_lowercase(),
+            merged: pr.merged_at.is_some(),
+            draft: pr.is_draft,
+            review_decision: pr.review_decision.filter(|s| !s.is_empty()),
+            checks_status,
+            additions: pr.additions,
+            deletions: pr.deletions,
+            head_branch: pr.head_ref_name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiChecksCommit {
+    status_check_rollup: Option<ApiRollupContexts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRollupContexts {
+    contexts: Nodes<ApiCheckContext>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "__typename")]
+enum ApiCheckContext {
+    CheckRun(ApiCheckRun),
+    StatusContext(ApiStatusContext),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiCheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    details_url: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiStatusContext {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiChecks {
+    commits: Nodes<ApiCommitNode<ApiChecksCommit>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIssueComment {
+    /// Missing for deleted accounts
+    user: Option<ApiLogin>,
+    #[serde(default)]
+    body: String,
+    created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiPRDetails {
+    merge_state_status: String,
+    mergeable: String,
+    review_decision: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCreatedPR {
+    number: u64,
+    html_url: String,
+}
+
+/// One query for the latest PR of every branch, aliased `b0`, `b1`, ...
+fn branches_query(count: usize) -> String {
+    let params: String = (0..count).map(|i| format!(", $b{}: String!", i)).collect();
+    let fields: String = (0..count)
+        .map(|i| {
+            format!(
+                "b{i}: pullRequests(headRefName: $b{i}, first: 1, \
+                 orderBy: {{field: CREATED_AT, direction: DESC}}) {{ nodes {{ ...PRFields }} }}\n"
+            )
+        })
+        .collect();
+    format!(
+        "query($owner: String!, $name: String!{}) {{\n\
+         repository(owner: $owner, name: $name) {{\n{}}}\n}}\n\
+         fragment PRFields on PullRequest {{ {} }}",
+        params, fields, PR_FIELDS
+    )
+}
+
+/// Talks to the GitHub REST and GraphQL APIs directly instead of going through `gh`.
+/// The host comes from each repository's remote, so GitHub Enterprise works the same way.
+pub struct GitHubApi;
+
+impl GitHubApi {
+    fn send(&self, host: &str, request: RequestBuilder) -> Result<Response, AppError> {
+        let token = token_for(host).ok_or(AppError::GhNotAuthenticated)?;
+        let response = request
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION)
+            .send()
+            .map_err(|e| request_error(host, e))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        if status == StatusCode::UNAUTHORIZED {
+            forget_tokens();
+            return Err(AppError::GhNotAuthenticated);
+        }
+        Err(response_error(response))
+    }
+
+    fn get<T: DeserializeOwned>(&self, host: &str, path: &str) -> Result<T, AppError> {
+        let url = format!("{}/{}", rest_base(host), path);
+        Ok(self.send(host, http_client()?.get(url))?.json()?)
+    }
+
+    /// GETs a list endpoint, following `Link` headers until every page is read.
+    fn get_all<T: DeserializeOwned>(&self, host: &str, path: &str) -> Result<Vec<T>, AppError> {
+        let mut url = Some(format!("{}/{}?per_page=100", rest_base(host), path));
+        let mut items = Vec::new();
+        while let Some(current) = url {
+            let response = self.send(host, http_client()?.get(current))?;
+            url = next_page(response.headers());
+            items.extend(response.json::<Vec<T>>()?);
+        }
+        Ok(items)
+    }
+
+    fn graphql<T: DeserializeOwned>(
+        &self,
+        host: &str,
+        query: &str,
+        variables: Value,
+    ) -> Result<T, AppError> {
+        let request = http_client()?
+            .post(graphql_url(host))
+            .json(&json!({ "query": query, "variables": variables }));
+        let response: GraphQLResponse<T> = self.send(host, request)?.json()?;
+        if !response.errors.is_empty() {
+            let messages: Vec<String> = response.errors.into_iter().map(|e| e.message).collect();
+            return Err(AppError::Api {
+                status: None,
+                message: messages.join("; "),
+            });
+        }
+        response.data.ok_or_else(|| AppError::Api {
+            status: None,
+            message: "GraphQL response without data".to_string(),
+        })
+    }
+
+    /// Selects `fields` of PR `number` in `remote`.
+    fn pull_request<T: DeserializeOwned>(
+        &self,
+        remote: &RemoteRepo,
+        number: u64,
+        fields: &str,
+    ) -> Result<T, AppError> {
+        let query = format!(
+            "query($owner: String!, $name: String!, $number: Int!) {{ \
+             repository(owner: $owner, name: $name) {{ pullRequest(number: $number) {{ {} }} }} }}",
+            fields
+        );
+        let variables = json!({ "owner": remote.owner, "name": remote.name, "number": number });
+        let data: RepositoryData<PullRequestData<T>> =
+            self.graphql(&remote.host, &query, variables)?;
+        data.repository
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Repository {}/{} not found",
+                    remote.owner, remote.name
+                ))
+            })?
+            .pull_request
+            .ok_or_else(|| AppError::NotFound(format!("Pull request #{} not found", number)))
+    }
+}
+
+impl Forge for GitHubApi {
+    fn current_user(&self, host: &str) -> Result<String, AppError> {
+        let user: ApiLogin = self.get(host, "user")?;
+        Ok(user.login)
+    }
+
+    fn pr_for_branch(&self, repo_path: &str, branch: &str) -> Result<Option<PRStatus>, AppError> {
+        let mut statuses = self.prs_for_branches(repo_path, &[branch.to_string()])?;
+        Ok(statuses.pop())
+    }
+
+    fn prs_for_branches(
+        &self,
+        repo_path: &str,
+        branches: &[String],
+    ) -> Result<Vec<PRStatus>, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let mut statuses = Vec::new();
+
+        for chunk in branches.chunks(BRANCHES_PER_QUERY) {
+            let mut variables = json!({ "owner": remote.owner, "name": remote.name });
+            for (i, branch) in chunk.iter().enumerate() {
+                variables[format!("b{}", i)] = json!(branch);
+            }
+            let data: RepositoryData<HashMap<String, Nodes<ApiPullRequest>>> =
+                self.graphql(&remote.host, &branches_query(chunk.len()), variables)?;
+            let mut by_alias = data.repository.ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Repository {}/{} not found",
+                    remote.owner, remote.name
+                ))
+            })?;
+
+            for i in 0..chunk.len() {
+                let pr = by_alias
+                    .remove(&format!("b{}", i))
+                    .and_then(|prs| prs.nodes.into_iter().next());
+                statuses.extend(pr.map(PRStatus::from));
+            }
+        }
+
+        Ok(statuses)
+    }
+
+    fn pr_status(&self, repo_path: &str, number: u64) -> Result<PRStatus, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let pr: ApiPullRequest = self.pull_request(&remote, number, PR_FIELDS)?;
+        Ok(pr.into())
+    }
+
+    fn pr_head(&self, repo_path: &str, number: u64) -> Result<PRHead, AppError> {
+        let remote = remote_repo(repo_path)?;
+        self.pull_request(&remote, number, "headRefName isCrossRepository")
+    }
+
+    fn repo_name(&self, repo_path: &str) -> Result<Option<String>, AppError> {
+        Ok(remote_repo(repo_path)
+            .ok()
+            .map(|remote| format!("{}/{}", remote.owner, remote.name)))
+    }
+
+    fn pr_checks(&self, repo_path: &str, number: u64) -> Result<PRChecksResult, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let pr: ApiChecks = self.pull_request(&remote, number, CHECKS_FIELDS)?;
+        let contexts = pr
+            .commits
+            .nodes
+            .into_iter()
+            .next()
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(|rollup| rollup.contexts.nodes)
+            .unwrap_or_default();
+
+        let checks: Vec<_> = contexts
+            .into_iter()
+            .map(|context| match context {
+                // Same states `gh pr checks` reports: the conclusion once a run completed
+                ApiCheckContext::CheckRun(run) => {
+                    let state = match run.status.as_str() {
+                        "COMPLETED" => run.conclusion.unwrap_or_default(),
+                        _ => run.status,
+                    };
+                    pr_check(
+                        run.name,
+                        &state,
+                        run.details_url,
+                        run.started_at,
+                        run.completed_at,
+                    )
+                }
+                ApiCheckContext::StatusContext(status) => pr_check(
+                    status.context,
+                    &status.state,
+                    status.target_url,
+                    status.created_at,
+                    None,
+                ),
+            })
+            .collect();
+
+        Ok(PRChecksResult {
+            overall_status: checks_overall_status(&checks),
+            checks,
+        })
+    }
+
+    fn pr_details(&self, repo_path: &str, number: u64) -> Result<PRDetailedInfo, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let pr: ApiPRDetails = self.pull_request(&remote, number, DETAILS_FIELDS)?;
+        let pulls = format!("repos/{}/{}/pulls/{}", remote.owner, remote.name, number);
+        // Over REST so every page is read; GraphQL caps a connection at 100 nodes
+        let issue_comments: Vec<ApiIssueComment> = self.get_all(
+            &remote.host,
+            &format!(
+                "repos/{}/{}/issues/{}/comments",
+                remote.owner, remote.name, number
+            ),
+        )?;
+        let reviews: Vec<RestApiReview> =
+            self.get_all(&remote.host, &format!("{}/reviews", pulls))?;
+        let review_comments: Vec<GhReviewComment> =
+            self.get_all(&remote.host, &format!("{}/comments", pulls))?;
+
+        let comments = issue_comments
+            .into_iter()
+            .map(|c| PRComment {
+                author: c
+                    .user
+                    .map(|a| a.login)
+                    .unwrap_or_else(|| "ghost".to_string()),
+                body: c.body,
+                created_at: c.created_at,
+                comment_type: "issue".to_string(),
+                state: None,
+                path: None,
+                line: None,
+                review_id: None,
+            })
+            .collect();
+
+        Ok(PRDetailedInfo {
+            merge_state_status: pr.merge_state_status,
+            mergeable: pr.mergeable,
+            comments: merge_pr_comments(comments, reviews, review_comments),
+            review_decision: pr.review_decision,
+        })
+    }
+
+    /// Opens the PR from the worktree's current branch, which has to be pushed already.
+    fn create_pr(
+        &self,
+        repo_path: &str,
+        pr: &NewPullRequest,
+        operation_id: Option<&str>,
+    ) -> Result<CreatePRResult, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let branch = current_branch(repo_path)?;
+
+        let url = format!(
+            "{}/repos/{}/{}/pulls",
+            rest_base(&remote.host),
+            remote.owner,
+            remote.name
+        );
+        let request = http_client()?.post(url).json(&json!({
+            "title": pr.title,
+            "body": pr.body,
+            "head": branch,
+            "base": pr.base,
+            "draft": pr.draft,
+        }));
+        if Operation::begin(operation_id).is_cancelled() {
+            return Err(AppError::Cancelled {
+                command: "Create pull request".to_string(),
+            });
+        }
+        let created: ApiCreatedPR = self.send(&remote.host, request)?.json()?;
+
+        Ok(CreatePRResult {
+            number: created.number,
+            url: created.html_url,
+        })
+    }
+}
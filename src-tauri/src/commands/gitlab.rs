@@ -0,0 +1,463 @@
+use parking_lot::Mutex;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use super::error::AppError;
+use super::forge::{
+    current_branch, host_config_value, http_client, next_page, remote_repo, request_error,
+    response_error, Forge, RemoteRepo,
+};
+use super::github::{
+    checks_overall_status, pr_check, CreatePRResult, NewPullRequest, PRChecksResult, PRComment,
+    PRDetailedInfo, PRHead, PRStatus,
+};
+use super::runner::Operation;
+
+/// Tokens by host; `None` records a lookup that found nothing
+static TOKENS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+
+/// Finds a token for `host` where glab would: the environment, then glab's config.yml.
+fn token_for(host: &str) -> Option<String> {
+    let tokens = TOKENS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(token) = tokens.lock().get(host) {
+        return token.clone();
+    }
+    let token = env_token().or_else(|| config_token(host));
+    tokens.lock().insert(host.to_string(), token.clone());
+    token
+}
+
+fn forget_tokens() {
+    if let Some(tokens) = TOKENS.get() {
+        tokens.lock().clear();
+    }
+}
+
+fn env_token() -> Option<String> {
+    ["GITLAB_TOKEN", "GITLAB_ACCESS_TOKEN"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|token| !token.trim().is_empty())
+}
+
+/// `$GLAB_CONFIG_DIR`, `$XDG_CONFIG_HOME/glab-cli`, or `~/.config/glab-cli`.
+fn glab_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("GLAB_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("glab-cli"));
+    }
+    dirs::home_dir().map(|home| home.join(".config").join("glab-cli"))
+}
+
+fn config_token(host: &str) -> Option<String> {
+    let contents = std::fs::read_to_string(glab_config_dir()?.join("config.yml")).ok()?;
+    host_config_value(&contents, host, "token")
+}
+
+fn not_authenticated(host: &str) -> AppError {
+    AppError::Api {
+        status: Some(401),
+        message: format!(
+            "Not logged in to {}. Run `glab auth login` or set GITLAB_TOKEN",
+            host
+        ),
+    }
+}
+
+/// `group/subgroup/name` as the URL-encoded project id the API expects.
+fn project_path(remote: &RemoteRepo) -> String {
+    format!(
+        "projects/{}%2F{}",
+        remote.owner.replace('/', "%2F"),
+        remote.name
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPipeline {
+    id: u64,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMergeRequest {
+    iid: u64,
+    title: String,
+    web_url: String,
+    /// opened, closed, locked or merged
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    source_branch: String,
+    source_project_id: u64,
+    target_project_id: u64,
+    #[serde(default)]
+    has_conflicts: bool,
+    merge_status: Option<String>,
+    detailed_merge_status: Option<String>,
+    /// Only included when fetching a single merge request
+    head_pipeline: Option<ApiPipeline>,
+}
+
+impl From<ApiMergeRequest> for PRStatus {
+    fn from(mr: ApiMergeRequest) -> Self {
+        let state = match mr.state.as_str() {
+            "opened" => "open",
+            "merged" => "merged",
+            _ => "closed",
+        };
+        let checks_status = mr.head_pipeline.and_then(|p| match p.status.as_str() {
+            "success" => Some("success".to_string()),
+            "failed" => Some("failure".to_string()),
+            "canceled" | "skipped" | "manual" => None,
+            _ => Some("pending".to_string()),
+        });
+        PRStatus {
+            number: mr.iid,
+            title: mr.title,
+            url: mr.web_url,
+            state: state.to_string(),
+            merged: mr.state == "merged",
+            draft: mr.draft,
+            // Approvals come from a separate endpoint, see `pr_details`
+            review_decision: None,
+            checks_status,
+            // GitLab has no line counts on merge requests
+            additions: 0,
+            deletions: 0,
+            head_branch: mr.source_branch,
+        }
+    }
+}
+
+/// GitLab's merge status in the GitHub terms the frontend understands:
+/// (`mergeStateStatus`, `mergeable`).
+fn merge_state(mr: &ApiMergeRequest) -> (String, String) {
+    let state = match mr.detailed_merge_status.as_deref() {
+        _ if mr.has_conflicts => "DIRTY",
+        Some("mergeable") => "CLEAN",
+        Some("conflict") => "DIRTY",
+        Some("need_rebase") => "BEHIND",
+        Some("draft_status") => "DRAFT",
+        Some("checking") | Some("unchecked") | Some("preparing") | None => "UNKNOWN",
+        Some(_) => "BLOCKED",
+    };
+    let mergeable = match mr.merge_status.as_deref() {
+        _ if mr.has_conflicts => "CONFLICTING",
+        Some("can_be_merged") => "MERGEABLE",
+        Some("cannot_be_merged") => "CONFLICTING",
+        _ => "UNKNOWN",
+    };
+    (state.to_string(), mergeable.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiJob {
+    name: String,
+    status: String,
+    #[serde(default)]
+    allow_failure: bool,
+    web_url: Option<String>,
+    started_at: Option<String>,
+    finished_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiNotePosition {
+    new_path: Option<String>,
+    new_line: Option<u32>,
+    old_line: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiNote {
+    body: String,
+    author: ApiUser,
+    created_at: String,
+    /// Notes GitLab writes itself, e.g. "added 1 commit"
+    #[serde(default)]
+    system: bool,
+    /// `DiffNote` for comments on a line of the diff
+    #[serde(rename = "type")]
+    note_type: Option<String>,
+    position: Option<ApiNotePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiApprovals {
+    #[serde(default)]
+    approved_by: Vec<serde_json::Value>,
+    #[serde(default)]
+    approvals_left: u32,
+}
+
+/// Merge requests through the GitLab REST API (v4), on gitlab.com or self-hosted.
+pub struct GitLabApi;
+
+impl GitLabApi {
+    fn send(&self, host: &str, request: RequestBuilder) -> Result<Response, AppError> {
+        let token = token_for(host).ok_or_else(|| not_authenticated(host))?;
+        let response = request
+            .bearer_auth(token)
+            .send()
+            .map_err(|e| request_error(host, e))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        if status == StatusCode::UNAUTHORIZED {
+            forget_tokens();
+            return Err(not_authenticated(host));
+        }
+        Err(response_error(response))
+    }
+
+    fn get<T: DeserializeOwned>(&self, host: &str, path: &str) -> Result<T, AppError> {
+        let url = format!("https://{}/api/v4/{}", host, path);
+        Ok(self.send(host, http_client()?.get(url))?.json()?)
+    }
+
+    /// GETs a list endpoint, following `Link` headers until every page is read.
+    fn get_all<T: DeserializeOwned>(
+        &self,
+        host: &str,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, AppError> {
+        let url = format!("https://{}/api/v4/{}", host, path);
+        let mut request = http_client()?
+            .get(url)
+            .query(query)
+            .query(&[("per_page", "100")]);
+        let mut items = Vec::new();
+        loop {
+            let response = self.send(host, request)?;
+            let next = next_page(response.headers());
+            items.extend(response.json::<Vec<T>>()?);
+            match next {
+                Some(url) => request = http_client()?.get(url),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    fn merge_request(&self, remote: &RemoteRepo, iid: u64) -> Result<ApiMergeRequest, AppError> {
+        self.get(
+            &remote.host,
+            &format!("{}/merge_requests/{}", project_path(remote), iid),
+        )
+    }
+
+    fn review_decision(&self, remote: &RemoteRepo, iid: u64) -> Option<String> {
+        let approvals: ApiApprovals = self
+            .get(
+                &remote.host,
+                &format!("{}/merge_requests/{}/approvals", project_path(remote), iid),
+            )
+            .ok()?;
+        if !approvals.approved_by.is_empty() && approvals.approvals_left == 0 {
+            Some("APPROVED".to_string())
+        } else if approvals.approvals_left > 0 {
+            Some("REVIEW_REQUIRED".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl Forge for GitLabApi {
+    fn current_user(&self, host: &str) -> Result<String, AppError> {
+        let user: ApiUser = self.get(host, "user")?;
+        Ok(user.username)
+    }
+
+    fn pr_for_branch(&self, repo_path: &str, branch: &str) -> Result<Option<PRStatus>, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let url = format!(
+            "https://{}/api/v4/{}/merge_requests",
+            remote.host,
+            project_path(&remote)
+        );
+        let request = http_client()?.get(url).query(&[
+            ("source_branch", branch),
+            ("state", "all"),
+            ("order_by", "created_at"),
+            ("sort", "desc"),
+            ("per_page", "1"),
+        ]);
+        let found: Vec<ApiMergeRequest> = self.send(&remote.host, request)?.json()?;
+
+        // Listed merge requests lack the pipeline, the single one has it
+        match found.first() {
+            Some(mr) => Ok(Some(self.merge_request(&remote, mr.iid)?.into())),
+            None => Ok(None),
+        }
+    }
+
+    fn pr_status(&self, repo_path: &str, number: u64) -> Result<PRStatus, AppError> {
+        let remote = remote_repo(repo_path)?;
+        Ok(self.merge_request(&remote, number)?.into())
+    }
+
+    fn pr_head(&self, repo_path: &str, number: u64) -> Result<PRHead, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let mr = self.merge_request(&remote, number)?;
+        Ok(PRHead {
+            is_cross_repository: mr.source_project_id != mr.target_project_id,
+            head_ref_name: mr.source_branch,
+        })
+    }
+
+    fn head_ref(&self, number: u64) -> String {
+        format!("merge-requests/{}/head", number)
+    }
+
+    fn repo_name(&self, repo_path: &str) -> Result<Option<String>, AppError> {
+        Ok(remote_repo(repo_path)
+            .ok()
+            .map(|remote| format!("{}/{}", remote.owner, remote.name)))
+    }
+
+    fn pr_checks(&self, repo_path: &str, number: u64) -> Result<PRChecksResult, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let project = project_path(&remote);
+        let pipelines: Vec<ApiPipeline> = self.get(
+            &remote.host,
+            &format!("{}/merge_requests/{}/pipelines", project, number),
+        )?;
+        // Newest first
+        let Some(pipeline) = pipelines.first() else {
+            return Ok(PRChecksResult {
+                checks: vec![],
+                overall_status: "none".to_string(),
+            });
+        };
+
+        let jobs: Vec<ApiJob> = self.get_all(
+            &remote.host,
+            &format!("{}/pipelines/{}/jobs", project, pipeline.id),
+            &[],
+        )?;
+        let checks: Vec<_> = jobs
+            .into_iter()
+            // Manual and skipped jobs never run on their own and would look pending forever
+            .filter(|job| job.status != "manual" && job.status != "skipped")
+            .map(|job| {
+                let state = match job.status.as_str() {
+                    "success" => "SUCCESS",
+                    "failed" if job.allow_failure => "SUCCESS",
+                    "failed" => "FAILURE",
+                    "canceled" => "CANCELLED",
+                    "running" => "IN_PROGRESS",
+                    _ => "PENDING",
+                };
+                pr_check(
+                    job.name,
+                    state,
+                    job.web_url,
+                    job.started_at,
+                    job.finished_at,
+                )
+            })
+            .collect();
+
+        Ok(PRChecksResult {
+            overall_status: checks_overall_status(&checks),
+            checks,
+        })
+    }
+
+    fn pr_details(&self, repo_path: &str, number: u64) -> Result<PRDetailedInfo, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let mr = self.merge_request(&remote, number)?;
+        let notes: Vec<ApiNote> = self.get_all(
+            &remote.host,
+            &format!("{}/merge_requests/{}/notes", project_path(&remote), number),
+            &[("sort", "asc"), ("order_by", "created_at")],
+        )?;
+
+        let comments = notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| {
+                let position = note
+                    .position
+                    .filter(|_| note.note_type.as_deref() == Some("DiffNote"));
+                PRComment {
+                    author: note.author.username,
+                    body: note.body,
+                    created_at: note.created_at,
+                    comment_type: match position {
+                        Some(_) => "review_thread".to_string(),
+                        None => "issue".to_string(),
+                    },
+                    state: None,
+                    line: position.as_ref().and_then(|p| p.new_line.or(p.old_line)),
+                    path: position.and_then(|p| p.new_path),
+                    review_id: None,
+                }
+            })
+            .collect();
+
+        let (merge_state_status, mergeable) = merge_state(&mr);
+        Ok(PRDetailedInfo {
+            merge_state_status,
+            mergeable,
+            comments,
+            review_decision: self.review_decision(&remote, number),
+        })
+    }
+
+    /// Opens the merge request from the worktree's current branch, which has to be pushed already.
+    fn create_pr(
+        &self,
+        repo_path: &str,
+        pr: &NewPullRequest,
+        operation_id: Option<&str>,
+    ) -> Result<CreatePRResult, AppError> {
+        let remote = remote_repo(repo_path)?;
+        let branch = current_branch(repo_path)?;
+        // Marking the title is how GitLab has always flagged drafts
+        let title = match pr.draft {
+            true => format!("Draft: {}", pr.title),
+            false => pr.title.clone(),
+        };
+
+        let url = format!(
+            "https://{}/api/v4/{}/merge_requests",
+            remote.host,
+            project_path(&remote)
+        );
+        let request = http_client()?.post(url).json(&json!({
+            "source_branch": branch,
+            "target_branch": pr.base,
+            "title": title,
+            "description": pr.body,
+        }));
+        if Operation::begin(operation_id).is_cancelled() {
+            return Err(AppError::Cancelled {
+                command: "Create merge request".to_string(),
+            });
+        }
+        let created: ApiMergeRequest = self.send(&remote.host, request)?.json()?;
+
+        Ok(CreatePRResult {
+            number: created.iid,
+            url: created.web_url,
+        })
+    }
+}
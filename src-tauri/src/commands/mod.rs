@@ -1,6 +1,25 @@
+pub mod agent;
+pub mod agent_adapter;
+pub mod attention;
 pub mod cli_tools;
+pub mod clone;
+pub mod commit_message;
+pub mod error;
+pub mod forge;
 pub mod git;
 pub mod github;
+pub mod github_api;
+pub mod gitlab;
+pub mod naming;
+pub mod pr_description;
 pub mod process;
+pub mod recording;
+pub mod registry;
+pub mod runner;
+pub mod shell_integration;
+pub mod sparse;
+pub mod storage;
 pub mod terminal;
+pub mod transcript;
 pub mod watcher;
+pub mod workspace;
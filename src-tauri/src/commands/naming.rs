@@ -0,0 +1,364 @@
+use chrono::Local;
+use git2::{BranchType, Config, ConfigLevel, Repository};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::error::AppError;
+use super::git::open_repo;
+
+const BRANCH_TEMPLATE_KEY: &str = "autopilot.branchTemplate";
+const WORKTREE_TEMPLATE_KEY: &str = "autopilot.worktreeTemplate";
+const USER_KEY: &str = "autopilot.user";
+const WORKTREE_ROOT_KEY: &str = "autopilot.worktreeRoot";
+
+const DEFAULT_BRANCH_TEMPLATE: &str = "{num}-{word}";
+
+const CITY_NAMES: &[&str] = &[
+    "tokyo", "paris", "london", "berlin", "sydney", "toronto", "mumbai", "cairo",
+    "rio", "seoul", "dublin", "oslo", "vienna", "prague", "lisbon", "athens",
+    "rome", "madrid", "amsterdam", "brussels", "zurich", "stockholm", "helsinki",
+    "warsaw", "budapest", "bangkok", "singapore", "jakarta", "manila", "hanoi",
+    "beijing", "shanghai", "hongkong", "taipei", "osaka", "kyoto", "melbourne",
+    "auckland", "vancouver", "montreal", "chicago", "boston", "seattle", "denver",
+    "austin", "miami", "atlanta", "phoenix", "portland", "detroit", "dallas",
+    "houston", "philadelphia", "sandiego", "sanfrancisco", "losangeles", "newyork",
+    "nairobi", "lagos", "capetown", "casablanca", "tunis", "algiers", "accra",
+    "lima", "bogota", "santiago", "buenosaires", "montevideo", "quito", "caracas",
+    "havana", "mexicocity", "guadalajara", "panama", "sanjose", "kingston",
+];
+
+/// How new worktrees are named and where they are placed on disk.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NamingConfig {
+    pub branch_template: Option<String>,
+    pub worktree_template: Option<String>,
+    pub user: Option<String>,
+    #[serde(default)]
+    pub worktree_root: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GeneratedName {
+    pub branch_name: String,
+    pub worktree_name: String,
+}
+
+struct TemplateValues<'a> {
+    user: &'a str,
+    date: &'a str,
+    seq: usize,
+    num: u32,
+    word: &'a str,
+    ticket: Option<&'a str>,
+}
+
+fn read_config_string(config: &Config, key: &str) -> Option<String> {
+    config
+        .get_string(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub fn load_naming_config(repo: &Repository) -> NamingConfig {
+    let config = match repo.config() {
+        Ok(config) => config,
+        Err(_) => return NamingConfig::default(),
+    };
+
+    NamingConfig {
+        branch_template: read_config_string(&config, BRANCH_TEMPLATE_KEY),
+        worktree_template: read_config_string(&config, WORKTREE_TEMPLATE_KEY),
+        user: read_config_string(&config, USER_KEY),
+        worktree_root: read_config_string(&config, WORKTREE_ROOT_KEY),
+    }
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    if path == "~" {
+        return dirs::home_dir().unwrap_or_else(|| PathBuf::from(path));
+    }
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Resolves the directory new worktrees are created in. `autopilot.worktreeRoot`
+/// may use `~` and a `{repo}` placeholder, e.g. `~/worktrees/{repo}`; relative
+/// roots are resolved against the repository. Returns `None` when the in-repo
+/// `.worktrees` default applies.
+pub fn configured_worktree_root(repo: &Repository, repo_path: &Path) -> Option<PathBuf> {
+    let root = load_naming_config(repo).worktree_root?;
+    let repo_name = repo_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("repo");
+
+    let expanded = expand_home(&root.replace("{repo}", repo_name));
+    if expanded.is_absolute() {
+        Some(expanded)
+    } else {
+        Some(repo_path.join(expanded))
+    }
+}
+
+/// The main repository's git dir, also when `repo` is a linked worktree.
+pub fn common_git_dir(repo: &Repository) -> PathBuf {
+    if repo.is_worktree() {
+        if let Ok(common) = std::fs::read_to_string(repo.path().join("commondir")) {
+            return repo.path().join(common.trim());
+        }
+    }
+    repo.path().to_path_buf()
+}
+
+/// Adds `.worktrees/` to `.git/info/exclude` so in-repo worktrees don't show up
+/// as untracked files in the main checkout.
+pub fn ensure_worktrees_excluded(repo: &Repository) -> Result<(), AppError> {
+    let info_dir = common_git_dir(repo).join("info");
+    let exclude_path = info_dir.join("exclude");
+
+    let existing = std::fs::read_to_string(&exclude_path).unwrap_or_default();
+    let already_excluded = existing
+        .lines()
+        .map(str::trim)
+        .any(|l| l == ".worktrees" || l == ".worktrees/" || l == "/.worktrees" || l == "/.worktrees/");
+    if already_excluded {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(&info_dir)?;
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str("/.worktrees/\n");
+    std::fs::write(&exclude_path, contents).map_err(AppError::from)
+}
+
+fn slugify(value: &str) -> String {
+    let mut slug = String::new();
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+fn resolve_user(repo: &Repository, naming: &NamingConfig) -> String {
+    if let Some(user) = &naming.user {
+        return slugify(user);
+    }
+
+    let from_email = repo
+        .config()
+        .ok()
+        .and_then(|c| c.get_string("user.email").ok())
+        .and_then(|email| email.split('@').next().map(String::from))
+        .filter(|local| !local.is_empty());
+
+    from_email
+        .or_else(|| std::env::var("USER").ok())
+        .map(|u| slugify(&u))
+        .unwrap_or_else(|| "user".to_string())
+}
+
+/// Collapses separators left behind by empty placeholders, e.g. a missing
+/// `{ticket}` in `{user}/{ticket}-{word}`.
+fn tidy_rendered_name(name: &str) -> String {
+    name.split('/')
+        .map(|segment| {
+            let mut out = String::new();
+            for c in segment.chars() {
+                let is_sep = c == '-' || c == '_';
+                if is_sep && (out.is_empty() || out.ends_with('-') || out.ends_with('_')) {
+                    continue;
+                }
+                out.push(c);
+            }
+            out.trim_end_matches(['-', '_']).to_string()
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn render_template(template: &str, values: &TemplateValues) -> String {
+    let rendered = template
+        .replace("{user}", values.user)
+        .replace("{date}", values.date)
+        .replace("{seq}", &values.seq.to_string())
+        .replace("{num}", &values.num.to_string())
+        .replace("{word}", values.word)
+        .replace("{ticket}", values.ticket.unwrap_or(""));
+    tidy_rendered_name(&rendered)
+}
+
+fn has_varying_placeholder(template: &str) -> bool {
+    ["{seq}", "{num}", "{word}"]
+        .iter()
+        .any(|p| template.contains(p))
+}
+
+/// Derives a directory-safe worktree name from a branch name.
+pub fn worktree_name_from_branch(branch: &str) -> String {
+    branch
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+pub fn validate_branch_name(name: &str) -> Result<(), AppError> {
+    match git2::Branch::name_is_valid(name) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppError::InvalidInput(format!("Invalid branch name: {}", name))),
+        Err(e) => Err(AppError::InvalidInput(format!(
+            "Invalid branch name {}: {}",
+            name,
+            e.message()
+        ))),
+    }
+}
+
+pub fn validate_worktree_name(name: &str) -> Result<(), AppError> {
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains('/')
+        || name.contains('\\')
+        || name.starts_with('.')
+    {
+        return Err(AppError::InvalidInput(format!("Invalid worktree name: {}", name)));
+    }
+    Ok(())
+}
+
+fn name_is_taken(repo: &Repository, branch_name: &str, worktree_name: &str) -> bool {
+    repo.find_branch(branch_name, BranchType::Local).is_ok()
+        || repo
+            .find_reference(&format!("refs/heads/{}", branch_name))
+            .is_ok()
+        || repo.find_worktree(worktree_name).is_ok()
+}
+
+pub fn generate_unique_name(
+    repo: &Repository,
+    ticket: Option<&str>,
+) -> Result<GeneratedName, AppError> {
+    let naming = load_naming_config(repo);
+    let branch_template = naming
+        .branch_template
+        .clone()
+        .unwrap_or_else(|| DEFAULT_BRANCH_TEMPLATE.to_string());
+
+    let user = resolve_user(repo, &naming);
+    let date = Local::now().format("%Y%m%d").to_string();
+    let ticket = ticket.map(str::trim).filter(|t| !t.is_empty());
+    let first_seq = repo.worktrees().map(|w| w.len()).unwrap_or(0) + 1;
+
+    let mut rng = rand::rng();
+    let attempts = if has_varying_placeholder(&branch_template) { 100 } else { 1 };
+
+    for attempt in 0..attempts {
+        let values = TemplateValues {
+            user: &user,
+            date: &date,
+            seq: first_seq + attempt,
+            num: rng.random_range(100..999),
+            word: CITY_NAMES[rng.random_range(0..CITY_NAMES.len())],
+            ticket,
+        };
+
+        let branch_name = render_template(&branch_template, &values);
+        let worktree_name = match &naming.worktree_template {
+            Some(template) => worktree_name_from_branch(&render_template(template, &values)),
+            None => worktree_name_from_branch(&branch_name),
+        };
+
+        validate_branch_name(&branch_name)?;
+        validate_worktree_name(&worktree_name)?;
+
+        if !name_is_taken(repo, &branch_name, &worktree_name) {
+            return Ok(GeneratedName {
+                branch_name,
+                worktree_name,
+            });
+        }
+    }
+
+    Err(AppError::Other("Could not generate unique worktree name".to_string()))
+}
+
+#[tauri::command]
+pub fn generate_worktree_name(
+    repo_path: String,
+    ticket: Option<String>,
+) -> Result<GeneratedName, AppError> {
+    let repo = open_repo(&repo_path)?;
+    generate_unique_name(&repo, ticket.as_deref())
+}
+
+#[tauri::command]
+pub fn get_naming_config(repo_path: String) -> Result<NamingConfig, AppError> {
+    let repo = open_repo(&repo_path)?;
+    Ok(load_naming_config(&repo))
+}
+
+fn write_config_string(config: &mut Config, key: &str, value: Option<&str>) -> Result<(), AppError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => config.set_str(key, v).map_err(AppError::from),
+        None => match config.remove(key) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        },
+    }
+}
+
+/// Saves naming and location settings to the repository's `.git/config`, or to the
+/// user's global git config when `global` is set.
+#[tauri::command]
+pub fn set_naming_config(
+    repo_path: String,
+    config: NamingConfig,
+    global: bool,
+) -> Result<(), AppError> {
+    if let Some(template) = &config.branch_template {
+        let sample = TemplateValues {
+            user: "user",
+            date: "20240101",
+            seq: 1,
+            num: 100,
+            word: "tokyo",
+            ticket: Some("ABC-1"),
+        };
+        validate_branch_name(&render_template(template, &sample))?;
+    }
+
+    let mut target = if global {
+        Config::open_default()
+            .and_then(|c| c.open_level(ConfigLevel::Global))?
+    } else {
+        let repo = open_repo(&repo_path)?;
+        repo.config()
+            .and_then(|c| c.open_level(ConfigLevel::Local))?
+    };
+
+    write_config_string(&mut target, BRANCH_TEMPLATE_KEY, config.branch_template.as_deref())?;
+    write_config_string(&mut target, WORKTREE_TEMPLATE_KEY, config.worktree_template.as_deref())?;
+    write_config_string(&mut target, USER_KEY, config.user.as_deref())?;
+    write_config_string(&mut target, WORKTREE_ROOT_KEY, config.worktree_root.as_deref())?;
+
+    Ok(())
+}
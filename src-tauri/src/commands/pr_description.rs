@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::agent_adapter::{adapter_for, run_prompt};
+use super::commit_message::{tag_content, truncate_text};
+use super::error::AppError;
+use super::git::{
+    branch_changed_files, find_base_commit, open_repo, resolve_base_branch, run_git, ChangedFile,
+};
+
+/// Where GitHub looks for a pull request template, relative to the repository root
+const TEMPLATE_PATHS: &[&str] = &[
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+];
+const MAX_COMMITS: &str = "50";
+const MAX_COMMIT_LOG_BYTES: usize = 12_000;
+const MAX_FILES_LISTED: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PRDescription {
+    pub title: String,
+    pub body: String,
+    /// Whether the body follows the repository's PR template
+    pub used_template: bool,
+}
+
+fn find_pr_template(worktree_path: &str) -> Option<String> {
+    TEMPLATE_PATHS
+        .iter()
+        .map(|p| Path::new(worktree_path).join(p))
+        .find(|p| p.is_file())
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .filter(|t| !t.trim().is_empty())
+}
+
+/// Subjects and bodies of the commits on this branch that aren't on `base` yet.
+/// The range starts at the resolved `base_commit`, which may be a local branch.
+fn branch_commits(
+    worktree_path: &str,
+    base: &str,
+    base_commit: &git2::Commit<'_>,
+) -> Result<String, AppError> {
+    let range = format!("{}..HEAD", base_commit.id());
+    let log = run_git(
+        worktree_path,
+        &[
+            "log",
+            "-n",
+            MAX_COMMITS,
+            "--no-merges",
+            "--format=- %s%n%b",
+            &range,
+        ],
+    )?;
+    if log.trim().is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No commits on this branch ahead of {}",
+            base
+        )));
+    }
+    Ok(truncate_text(&log, MAX_COMMIT_LOG_BYTES).0.to_string())
+}
+
+fn files_summary(files: &[ChangedFile]) -> String {
+    let mut summary: String = files
+        .iter()
+        .take(MAX_FILES_LISTED)
+        .map(|f| {
+            format!(
+                "{} {} (+{} -{})\n",
+                f.status, f.path, f.additions, f.deletions
+            )
+        })
+        .collect();
+    if files.len() > MAX_FILES_LISTED {
+        summary.push_str(&format!(
+            "... and {} more files\n",
+            files.len() - MAX_FILES_LISTED
+        ));
+    }
+    summary
+}
+
+fn build_prompt(commits: &str, files: &str, template: Option<&str>) -> String {
+    let mut prompt = String::from(
+        "Write a pull request title and description for the branch described below.\n\
+         The title is one line, at most 72 characters. The description explains what changed and why, in Markdown.\n",
+    );
+    match template {
+        Some(template) => {
+            prompt.push_str(
+                "Fill in this pull request template for the description, keeping its headings. \
+                 Leave out checklist items and sections that don't apply.\n<template>\n",
+            );
+            prompt.push_str(template.trim());
+            prompt.push_str("\n</template>\n");
+        }
+        None => prompt.push_str(
+            "Start the description with a short summary, followed by a list of the main changes.\n",
+        ),
+    }
+    prompt.push_str(
+        "\nReturn ONLY <title>...</title> followed by <body>...</body>. No other text.\n\
+         \nCommits:\n",
+    );
+    prompt.push_str(commits);
+    prompt.push_str("\nChanged files:\n");
+    prompt.push_str(files);
+    prompt
+}
+
+/// Asks `agent` for a PR title and body based on the branch's commits, changed
+/// files and the repository's PR template. The result is meant for `create_pr`.
+#[tauri::command]
+pub async fn generate_pr_description(
+    app: AppHandle,
+    worktree_path: String,
+    agent: String,
+    base: Option<String>,
+    operation_id: Option<String>,
+) -> Result<PRDescription, AppError> {
+    let adapter = adapter_for(&app, &agent)?;
+
+    tokio::task::spawn_blocking(move || {
+        let repo = open_repo(&worktree_path)?;
+        let base = match base.filter(|b| !b.trim().is_empty()) {
+            Some(base) => base,
+            None => resolve_base_branch(&app, &repo)?,
+        };
+        let base_commit = find_base_commit(&repo, &base)?;
+        let files = branch_changed_files(&repo, &base_commit)?;
+        let commits = branch_commits(&worktree_path, &base, &base_commit)?;
+        let template = find_pr_template(&worktree_path);
+        let prompt = build_prompt(&commits, &files_summary(&files), template.as_deref());
+
+        let output = run_prompt(
+            adapter.as_ref(),
+            &worktree_path,
+            &prompt,
+            &[],
+            operation_id.as_deref(),
+        )?;
+        let title = tag_content(&output, "title")
+            .ok_or("Could not extract a PR title from the AI response")?;
+        let body = tag_content(&output, "body").unwrap_or_default();
+
+        Ok(PRDescription {
+            title,
+            body,
+            used_template: template.is_some(),
+        })
+    })
+    .await?
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::State;
+
+use super::agent::{worktrees_with_running_agents, AgentTasks};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -81,10 +84,25 @@ fn create_process_refresh_system() -> System {
     system
 }
 
+/// Agent tasks started through `start_agent_task` are known for sure, so
+/// they're checked before falling back to process-name heuristics.
+fn has_agent_task(running_agents: &[String], worktree_path: &Path) -> bool {
+    running_agents
+        .iter()
+        .any(|task_worktree| is_process_in_worktree(Some(Path::new(task_worktree)), worktree_path))
+}
+
 #[tauri::command]
-pub fn get_worktree_process_status(worktree_path: String) -> ProcessStatus {
-    let system = create_process_refresh_system();
+pub fn get_worktree_process_status(
+    agent_tasks: State<'_, AgentTasks>,
+    worktree_path: String,
+) -> ProcessStatus {
     let worktree_path = Path::new(&worktree_path);
+    if has_agent_task(&worktrees_with_running_agents(&agent_tasks), worktree_path) {
+        return ProcessStatus::AgentRunning;
+    }
+
+    let system = create_process_refresh_system();
 
     let mut has_dev_server = false;
     let mut has_agent = false;
@@ -124,13 +142,22 @@ pub fn get_worktree_process_status(worktree_path: String) -> ProcessStatus {
 
 #[tauri::command]
 pub fn get_all_worktrees_process_status(
+    agent_tasks: State<'_, AgentTasks>,
     worktree_paths: Vec<String>,
 ) -> std::collections::HashMap<String, ProcessStatus> {
     let system = create_process_refresh_system();
+    let running_agents = worktrees_with_running_agents(&agent_tasks);
 
     let mut results: std::collections::HashMap<String, ProcessStatus> = worktree_paths
         .iter()
-        .map(|p| (p.clone(), ProcessStatus::None))
+        .map(|p| {
+            let status = if has_agent_task(&running_agents, Path::new(p)) {
+                ProcessStatus::AgentRunning
+            } else {
+                ProcessStatus::None
+            };
+            (p.clone(), status)
+        })
         .collect();
 
     let worktree_paths_parsed: Vec<(&String, std::path::PathBuf)> = worktree_paths
@@ -0,0 +1,257 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tauri::AppHandle;
+
+use super::error::AppError;
+use super::git::open_repo;
+use super::storage::{load_json, save_json};
+use super::transcript::{cast_event_line, cast_header};
+
+const SETTINGS_FILE: &str = "recording.json";
+/// Inside the worktree's own git dir, so recordings go away with the worktree
+const RECORDINGS_DIR: &str = "autopilot/recordings";
+
+/// Recordings a live session is still writing, which retention must leave alone
+static OPEN_RECORDINGS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+
+fn open_recordings() -> &'static Mutex<HashSet<PathBuf>> {
+    OPEN_RECORDINGS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingSettings {
+    pub enabled: bool,
+    /// Recordings older than this are deleted; 0 keeps them forever
+    pub retention_days: u32,
+    /// Most recordings kept per worktree; 0 means no limit
+    pub max_per_worktree: usize,
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 14,
+            max_per_worktree: 50,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingInfo {
+    pub path: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub title: Option<String>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    pub size_bytes: u64,
+}
+
+/// Appends asciicast v2 events to a `.cast` file as they happen.
+pub struct CastRecorder {
+    file: BufWriter<File>,
+    path: PathBuf,
+    started: Instant,
+}
+
+impl CastRecorder {
+    fn create(path: &Path, cols: u16, rows: u16, title: Option<&str>) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "{}", cast_header(cols, rows, Utc::now(), title))?;
+        open_recordings().lock().insert(path.to_path_buf());
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            started: Instant::now(),
+        })
+    }
+
+    fn event(&mut self, kind: &str, data: &str) {
+        let line = cast_event_line(self.started.elapsed().as_secs_f64(), kind, data);
+        let _ = writeln!(self.file, "{}", line);
+    }
+
+    pub fn output(&mut self, data: &str) {
+        if !data.is_empty() {
+            self.event("o", data);
+            // Keep the file usable if the app dies mid-session
+            let _ = self.file.flush();
+        }
+    }
+
+    pub fn input(&mut self, data: &str) {
+        self.event("i", data);
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    pub fn finish(&mut self) {
+        let _ = self.file.flush();
+    }
+}
+
+impl Drop for CastRecorder {
+    fn drop(&mut self) {
+        open_recordings().lock().remove(&self.path);
+    }
+}
+
+fn recordings_dir(worktree_path: &str) -> Result<PathBuf, AppError> {
+    let repo = open_repo(worktree_path)?;
+    Ok(repo.path().join(RECORDINGS_DIR))
+}
+
+fn load_settings(app: &AppHandle) -> RecordingSettings {
+    load_json(app, SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Deletes recordings past the retention age, then the oldest beyond the count limit.
+/// Recordings still being written count towards the limit but are kept.
+fn apply_retention(dir: &Path, settings: &RecordingSettings) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, std::time::SystemTime)> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+        .collect();
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    let cutoff = (settings.retention_days > 0).then(|| {
+        std::time::SystemTime::now()
+            - std::time::Duration::from_secs(u64::from(settings.retention_days) * 24 * 60 * 60)
+    });
+
+    let open = open_recordings().lock().clone();
+    for (index, (path, modified)) in files.iter().enumerate() {
+        if open.contains(path) {
+            continue;
+        }
+        let too_old = cutoff.is_some_and(|cutoff| *modified < cutoff);
+        let too_many = settings.max_per_worktree > 0 && index >= settings.max_per_worktree;
+        if too_old || too_many {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Starts a recording for a terminal in `worktree_path` when recording is enabled.
+pub fn start_recording(
+    app: &AppHandle,
+    worktree_path: Option<&str>,
+    terminal_id: &str,
+    cols: u16,
+    rows: u16,
+    title: Option<&str>,
+) -> Option<CastRecorder> {
+    let settings = load_settings(app);
+    if !settings.enabled {
+        return None;
+    }
+
+    let dir = recordings_dir(worktree_path?).ok()?;
+    std::fs::create_dir_all(&dir).ok()?;
+
+    let file_name = format!("{}-{}.cast", Utc::now().format("%Y%m%dT%H%M%S"), terminal_id);
+    let recorder = CastRecorder::create(&dir.join(file_name), cols, rows, title).ok()?;
+    // Only now, so the new recording counts towards the limit
+    apply_retention(&dir, &settings);
+    Some(recorder)
+}
+
+fn read_recording_info(path: &Path) -> Option<RecordingInfo> {
+    let size_bytes = std::fs::metadata(path).ok()?.len();
+    let mut header = String::new();
+    BufReader::new(File::open(path).ok()?).read_line(&mut header).ok()?;
+    let header: serde_json::Value = serde_json::from_str(&header).unwrap_or_default();
+
+    Some(RecordingInfo {
+        path: path.to_string_lossy().to_string(),
+        started_at: header["timestamp"]
+            .as_i64()
+            .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        title: header["title"].as_str().map(String::from),
+        width: header["width"].as_u64().map(|w| w as u16),
+        height: header["height"].as_u64().map(|h| h as u16),
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub fn get_recording_settings(app: AppHandle) -> RecordingSettings {
+    load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_recording_settings(app: AppHandle, settings: RecordingSettings) -> Result<(), AppError> {
+    save_json(&app, SETTINGS_FILE, &settings)
+}
+
+/// Lists the asciicast recordings of a worktree, newest first.
+#[tauri::command]
+pub fn list_recordings(worktree_path: String) -> Result<Vec<RecordingInfo>, AppError> {
+    let dir = recordings_dir(&worktree_path)?;
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut recordings: Vec<RecordingInfo> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|p| read_recording_info(&p))
+        .collect();
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+
+    Ok(recordings)
+}
+
+/// `path` if it is one of `worktree_path`'s recordings; the webview can't name other files.
+fn recording_path(worktree_path: &str, path: String) -> Result<PathBuf, AppError> {
+    let dir = recordings_dir(worktree_path)?;
+    let path = PathBuf::from(path);
+    if path.parent() != Some(dir.as_path()) {
+        return Err(AppError::InvalidInput("Not a recording of this worktree".to_string()));
+    }
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn delete_recording(worktree_path: String, path: String) -> Result<(), AppError> {
+    let path = recording_path(&worktree_path, path)?;
+    std::fs::remove_file(&path).map_err(AppError::from)
+}
+
+/// Deletes recordings of a worktree past the retention policy right away.
+#[tauri::command]
+pub fn prune_recordings(app: AppHandle, worktree_path: String) -> Result<(), AppError> {
+    let settings = load_settings(&app);
+    apply_retention(&recordings_dir(&worktree_path)?, &settings);
+    Ok(())
+}
+
+/// Reads a recording back as (time, kind, data) events for in-app replay.
+#[tauri::command]
+pub fn read_recording(
+    worktree_path: String,
+    path: String,
+) -> Result<Vec<(f64, String, String)>, AppError> {
+    let file = File::open(recording_path(&worktree_path, path)?)?;
+    let events = BufReader::new(file)
+        .lines()
+        .skip(1)
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    Ok(events)
+}
@@ -0,0 +1,328 @@
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::error::AppError;
+use super::git::{discover_repository, RepoInfo};
+use super::naming::expand_home;
+use super::runner::ProcessRunner;
+use super::storage::{load_json, save_json};
+
+const REPOSITORIES_FILE: &str = "repositories.json";
+const DEFAULT_SCAN_DEPTH: usize = 4;
+const SCAN_SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+/// Hooks often install dependencies, which can take a while in a fresh worktree
+const HOOK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Held across load -> modify -> save so concurrent commands don't drop each other's changes
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RepoHooks {
+    /// Shell command run in a new worktree after it's created
+    pub post_create: Option<String>,
+    /// Shell command run in a worktree before it's deleted
+    pub pre_delete: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RepoSettings {
+    /// Branch `create_worktree_auto` starts from; origin's main/master when unset
+    pub base_branch: Option<String>,
+    #[serde(default)]
+    pub hooks: RepoHooks,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegisteredRepo {
+    pub path: String,
+    pub name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    pub last_opened: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub settings: RepoSettings,
+    /// Set when the path no longer holds a git repository (moved or deleted)
+    #[serde(default, skip_deserializing)]
+    pub missing: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct RepoRegistry {
+    repositories: Vec<RegisteredRepo>,
+}
+
+fn load_registry(app: &AppHandle) -> Result<RepoRegistry, AppError> {
+    load_json(app, REPOSITORIES_FILE)
+}
+
+fn save_registry(app: &AppHandle, registry: &RepoRegistry) -> Result<(), AppError> {
+    save_json(app, REPOSITORIES_FILE, registry)
+}
+
+/// Loads the registry, applies `change` and saves it, all under `REGISTRY_LOCK`.
+fn modify_registry<T>(
+    app: &AppHandle,
+    change: impl FnOnce(&mut RepoRegistry) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let _guard = REGISTRY_LOCK.lock();
+    let mut registry = load_registry(app)?;
+    let result = change(&mut registry)?;
+    save_registry(app, &registry)?;
+    Ok(result)
+}
+
+/// Settings of the registered repository at `repo_path`; defaults if it isn't registered.
+pub fn repo_settings(app: &AppHandle, repo_path: &str) -> RepoSettings {
+    // Paths derived from a worktree's git dir contain `..`
+    let canonical = |p: &str| std::fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p));
+    let path = canonical(repo_path);
+    load_registry(app)
+        .ok()
+        .and_then(|registry| {
+            registry
+                .repositories
+                .into_iter()
+                .find(|r| canonical(&r.path) == path)
+        })
+        .map(|r| r.settings)
+        .unwrap_or_default()
+}
+
+/// Runs a repository hook command with `sh -c` in `dir`. Unset hooks do nothing.
+pub fn run_hook(
+    name: &str,
+    command: Option<&str>,
+    dir: &Path,
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(command) = command.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Ok(());
+    };
+    ProcessRunner::new("sh")
+        .args(["-c", command])
+        .cwd(dir)
+        .label(format!("{} hook", name))
+        .timeout(HOOK_TIMEOUT)
+        .operation(operation_id)
+        .run()?;
+    Ok(())
+}
+
+fn is_missing(path: &str) -> bool {
+    Repository::open(path).is_err()
+}
+
+fn find_repo<'a>(registry: &'a mut RepoRegistry, path: &str) -> Result<&'a mut RegisteredRepo, AppError> {
+    registry
+        .repositories
+        .iter_mut()
+        .find(|r| r.path == path)
+        .ok_or_else(|| AppError::NotFound(format!("Repository not registered: {}", path)))
+}
+
+/// Adds a repository (or refreshes it if already known) without saving.
+fn upsert(registry: &mut RepoRegistry, info: RepoInfo, opened: bool) -> RegisteredRepo {
+    let now = opened.then(Utc::now);
+    let entry = match registry.repositories.iter_mut().find(|r| r.path == info.path) {
+        Some(existing) => {
+            existing.name = info.name;
+            if now.is_some() {
+                existing.last_opened = now;
+            }
+            existing
+        }
+        None => {
+            registry.repositories.push(RegisteredRepo {
+                path: info.path,
+                name: info.name,
+                display_name: None,
+                pinned: false,
+                last_opened: now,
+                settings: RepoSettings::default(),
+                missing: false,
+            });
+            registry.repositories.last_mut().unwrap()
+        }
+    };
+    entry.missing = false;
+    entry.clone()
+}
+
+/// Registers a repository, e.g. right after it was opened or cloned.
+pub fn register_path(app: &AppHandle, path: &str) -> Result<RegisteredRepo, AppError> {
+    let info = discover_repository(path.to_string())?;
+    modify_registry(app, |registry| Ok(upsert(registry, info, true)))
+}
+
+fn scan_dir(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    // A `.git` file means a linked worktree or submodule, which belong to their parent
+    if dir.join(".git").is_dir() {
+        found.push(dir.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if !file_type.is_dir() {
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SCAN_SKIP_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        scan_dir(&entry.path(), depth - 1, found);
+    }
+}
+
+/// Lists known repositories, pinned first, then most recently opened.
+#[tauri::command]
+pub fn list_repositories(app: AppHandle) -> Result<Vec<RegisteredRepo>, AppError> {
+    let mut repos = load_registry(&app)?.repositories;
+    for repo in &mut repos {
+        repo.missing = is_missing(&repo.path);
+    }
+    repos.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+    Ok(repos)
+}
+
+#[tauri::command]
+pub fn register_repository(app: AppHandle, path: String) -> Result<RegisteredRepo, AppError> {
+    register_path(&app, &path)
+}
+
+#[tauri::command]
+pub fn update_repository(
+    app: AppHandle,
+    path: String,
+    display_name: Option<String>,
+    pinned: Option<bool>,
+    settings: Option<RepoSettings>,
+) -> Result<RegisteredRepo, AppError> {
+    modify_registry(&app, |registry| {
+        let repo = find_repo(registry, &path)?;
+
+        if let Some(display_name) = display_name {
+            let display_name = display_name.trim();
+            repo.display_name = (!display_name.is_empty()).then(|| display_name.to_string());
+        }
+        if let Some(pinned) = pinned {
+            repo.pinned = pinned;
+        }
+        if let Some(settings) = settings {
+            repo.settings = settings;
+        }
+        repo.missing = is_missing(&repo.path);
+
+        Ok(repo.clone())
+    })
+}
+
+#[tauri::command]
+pub fn remove_repository(app: AppHandle, path: String) -> Result<(), AppError> {
+    modify_registry(&app, |registry| {
+        registry.repositories.retain(|r| r.path != path);
+        Ok(())
+    })
+}
+
+/// Points a registered repository at its new location, keeping its settings.
+#[tauri::command]
+pub fn relocate_repository(
+    app: AppHandle,
+    old_path: String,
+    new_path: String,
+) -> Result<RegisteredRepo, AppError> {
+    let info = discover_repository(new_path)?;
+    modify_registry(&app, |registry| {
+        if info.path != old_path && registry.repositories.iter().any(|r| r.path == info.path) {
+            return Err(AppError::InvalidInput(format!(
+                "Repository already registered: {}",
+                info.path
+            )));
+        }
+
+        let repo = find_repo(registry, &old_path)?;
+        repo.path = info.path;
+        repo.name = info.name;
+        repo.missing = false;
+
+        Ok(repo.clone())
+    })
+}
+
+/// Drops repositories whose path no longer exists. Returns the removed paths.
+#[tauri::command]
+pub fn prune_missing_repositories(app: AppHandle) -> Result<Vec<String>, AppError> {
+    modify_registry(&app, |registry| {
+        let (missing, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut registry.repositories)
+            .into_iter()
+            .partition(|r| is_missing(&r.path));
+        registry.repositories = kept;
+        Ok(missing.into_iter().map(|r| r.path).collect())
+    })
+}
+
+/// Finds git repositories below `root` (e.g. `~/code`) that aren't registered yet.
+#[tauri::command]
+pub async fn scan_for_repositories(
+    app: AppHandle,
+    root: String,
+    max_depth: Option<usize>,
+) -> Result<Vec<RepoInfo>, AppError> {
+    let root = expand_home(&root);
+    if !root.is_dir() {
+        return Err(AppError::NotFound(format!("Not a directory: {}", root.display())));
+    }
+
+    let known: Vec<String> = load_registry(&app)?
+        .repositories
+        .into_iter()
+        .map(|r| r.path)
+        .collect();
+
+    tokio::task::spawn_blocking(move || {
+        let mut found = Vec::new();
+        scan_dir(&root, max_depth.unwrap_or(DEFAULT_SCAN_DEPTH), &mut found);
+
+        found
+            .into_iter()
+            .filter_map(|path| discover_repository(path.to_string_lossy().to_string()).ok())
+            .filter(|info| !known.contains(&info.path))
+            .collect()
+    })
+    .await
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn import_repositories(app: AppHandle, paths: Vec<String>) -> Result<Vec<RegisteredRepo>, AppError> {
+    let infos = paths
+        .into_iter()
+        .map(discover_repository)
+        .collect::<Result<Vec<_>, _>>()?;
+    modify_registry(&app, |registry| {
+        Ok(infos
+            .into_iter()
+            .map(|info| upsert(registry, info, false))
+            .collect())
+    })
+}
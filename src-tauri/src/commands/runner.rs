@@ -0,0 +1,437 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Applies to every external command unless the caller picks its own
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the output pipes may stay open once the process has exited. A grandchild,
+/// e.g. an ssh ControlPersist master, can hold them open indefinitely.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A registered operation: its cancellation flag and how many `Operation`s hold it
+struct OperationEntry {
+    cancelled: Arc<AtomicBool>,
+    holders: usize,
+}
+
+/// The operations currently running, by operation id
+static OPERATIONS: OnceLock<Mutex<HashMap<String, OperationEntry>>> = OnceLock::new();
+
+fn operations() -> &'static Mutex<HashMap<String, OperationEntry>> {
+    OPERATIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Keeps an operation id registered, and cancellable, until dropped. Commands that
+/// run several steps hold one for their whole duration, so a cancel that arrives
+/// between two processes still stops the ones after it. Runs sharing an id share
+/// the registration and are cancelled together.
+pub struct Operation {
+    id: Option<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Operation {
+    pub fn begin(operation_id: Option<&str>) -> Self {
+        let Some(id) = operation_id else {
+            return Self {
+                id: None,
+                cancelled: Arc::new(AtomicBool::new(false)),
+            };
+        };
+        let mut operations = operations().lock();
+        let entry = operations
+            .entry(id.to_string())
+            .or_insert_with(|| OperationEntry {
+                cancelled: Arc::new(AtomicBool::new(false)),
+                holders: 0,
+            });
+        entry.holders += 1;
+        Self {
+            id: Some(id.to_string()),
+            cancelled: entry.cancelled.clone(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        let Some(id) = &self.id else {
+            return;
+        };
+        let mut operations = operations().lock();
+        if let Some(entry) = operations.get_mut(id) {
+            entry.holders -= 1;
+            if entry.holders == 0 {
+                operations.remove(id);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessOutputLine {
+    pub operation_id: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RunError {
+    /// The process couldn't be started or waited on
+    Io {
+        command: String,
+        message: String,
+    },
+    /// The process exited unsuccessfully
+    Failed {
+        command: String,
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+    TimedOut {
+        command: String,
+        seconds: u64,
+    },
+    Cancelled {
+        command: String,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Io { command, message } => {
+                write!(f, "Failed to run {}: {}", command, message)
+            }
+            RunError::Failed {
+                command, stderr, ..
+            } => write!(f, "{} failed: {}", command, stderr),
+            RunError::TimedOut { command, seconds } => {
+                write!(f, "{} timed out after {}s", command, seconds)
+            }
+            RunError::Cancelled { command } => write!(f, "{} was cancelled", command),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ProcessOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+type LineCallback<'a> = Box<dyn FnMut(OutputStream, &str) + 'a>;
+
+/// Runs an external command with a timeout, optional cancellation through
+/// `cancel_operation`, and line-by-line access to its output while it runs.
+pub struct ProcessRunner<'a> {
+    program: String,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    label: Option<String>,
+    timeout: Option<Duration>,
+    operation_id: Option<String>,
+    emit_to: Option<AppHandle>,
+    on_line: Option<LineCallback<'a>>,
+}
+
+impl<'a> ProcessRunner<'a> {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            cwd: None,
+            label: None,
+            timeout: Some(DEFAULT_TIMEOUT),
+            operation_id: None,
+            emit_to: None,
+            on_line: None,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_string()));
+        self
+    }
+
+    pub fn cwd(mut self, dir: impl AsRef<Path>) -> Self {
+        self.cwd = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Name used in error messages; defaults to the program and its first argument.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// For commands that legitimately run for a long time; they should be cancellable instead.
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Makes the process cancellable via `cancel_operation(operation_id)`.
+    pub fn operation(mut self, operation_id: Option<&str>) -> Self {
+        self.operation_id = operation_id.map(String::from);
+        self
+    }
+
+    /// Streams output lines to the frontend as `process-output` events. Needs an operation id.
+    pub fn emit_output(mut self, app: &AppHandle) -> Self {
+        self.emit_to = Some(app.clone());
+        self
+    }
+
+    /// Called for every output line while the process runs. Lines end at `\n` or `\r`,
+    /// so progress counters that redraw in place come through as separate lines.
+    pub fn on_line(mut self, callback: impl FnMut(OutputStream, &str) + 'a) -> Self {
+        self.on_line = Some(Box::new(callback));
+        self
+    }
+
+    fn command_label(&self) -> String {
+        if let Some(label) = &self.label {
+            return label.clone();
+        }
+        let name = Path::new(&self.program)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.program.clone());
+        match self.args.first() {
+            Some(first) => format!("{} {}", name, first),
+            None => name,
+        }
+    }
+
+    fn handle_line(&mut self, stream: OutputStream, line: &str) {
+        if let Some(callback) = self.on_line.as_mut() {
+            callback(stream, line);
+        }
+        if let (Some(app), Some(operation_id)) = (&self.emit_to, &self.operation_id) {
+            let _ = app.emit(
+                "process-output",
+                ProcessOutputLine {
+                    operation_id: operation_id.clone(),
+                    stream,
+                    line: line.to_string(),
+                },
+            );
+        }
+    }
+
+    /// Runs the process to completion and returns its output whatever the exit code.
+    pub fn output(mut self) -> Result<ProcessOutput, RunError> {
+        let command = self.command_label();
+        let io_error = |e: std::io::Error| RunError::Io {
+            command: command.clone(),
+            message: e.to_string(),
+        };
+
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+        // Its own process group, so killing it also stops what it started (hooks, git helpers)
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        let operation = Operation::begin(self.operation_id.as_deref());
+        if operation.is_cancelled() {
+            return Err(RunError::Cancelled { command });
+        }
+        let mut child = cmd.spawn().map_err(io_error)?;
+
+        let (tx, rx) = mpsc::channel();
+        let stdout_reader = spawn_reader(child.stdout.take(), OutputStream::Stdout, tx.clone());
+        let stderr_reader = spawn_reader(child.stderr.take(), OutputStream::Stderr, tx);
+
+        let deadline = self.timeout.map(|t| Instant::now() + t);
+        let status = loop {
+            if let Ok((stream, line)) = rx.recv_timeout(POLL_INTERVAL) {
+                self.handle_line(stream, &line);
+            }
+            if let Some(status) = child.try_wait().map_err(io_error)? {
+                break status;
+            }
+            if operation.is_cancelled() {
+                kill(&mut child);
+                return Err(RunError::Cancelled { command });
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                kill(&mut child);
+                return Err(RunError::TimedOut {
+                    command,
+                    seconds: self.timeout.unwrap_or_default().as_secs(),
+                });
+            }
+        };
+
+        let drain_deadline = Instant::now() + DRAIN_TIMEOUT;
+        while !(stdout_reader.is_finished() && stderr_reader.is_finished())
+            && Instant::now() < drain_deadline
+        {
+            if let Ok((stream, line)) = rx.recv_timeout(POLL_INTERVAL) {
+                self.handle_line(stream, &line);
+            }
+        }
+        // Finished readers have queued everything they read; ones still blocked on a
+        // pipe held open elsewhere are left behind with what they have so far
+        for (stream, line) in rx.try_iter() {
+            self.handle_line(stream, &line);
+        }
+
+        Ok(ProcessOutput {
+            code: status.code(),
+            stdout: String::from_utf8_lossy(&stdout_reader.take()).to_string(),
+            stderr: String::from_utf8_lossy(&stderr_reader.take()).to_string(),
+        })
+    }
+
+    /// Runs the process and returns its stdout, or `RunError::Failed` on a non-zero exit.
+    pub fn run(self) -> Result<String, RunError> {
+        let command = self.command_label();
+        let output = self.output()?;
+        if !output.success() {
+            return Err(RunError::Failed {
+                command,
+                code: output.code,
+                stdout: output.stdout,
+                stderr: output.stderr,
+            });
+        }
+        Ok(output.stdout)
+    }
+}
+
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// A thread reading one of the child's output pipes.
+struct PipeReader {
+    handle: JoinHandle<()>,
+    raw: Arc<Mutex<Vec<u8>>>,
+}
+
+impl PipeReader {
+    fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Everything read so far.
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.raw.lock())
+    }
+}
+
+/// Collects a pipe's raw bytes while sending its lines over `tx` as they arrive.
+fn spawn_reader<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    stream: OutputStream,
+    tx: Sender<(OutputStream, String)>,
+) -> PipeReader {
+    let raw = Arc::new(Mutex::new(Vec::new()));
+    let collected = raw.clone();
+    let handle = thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut line = Vec::new();
+        let mut buf = [0u8; 4096];
+
+        while let Ok(n) = pipe.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            collected.lock().extend_from_slice(&buf[..n]);
+            for &byte in &buf[..n] {
+                if byte != b'\n' && byte != b'\r' {
+                    line.push(byte);
+                    continue;
+                }
+                if !line.is_empty() {
+                    let _ = tx.send((stream, String::from_utf8_lossy(&line).to_string()));
+                    line.clear();
+                }
+            }
+        }
+        if !line.is_empty() {
+            let _ = tx.send((stream, String::from_utf8_lossy(&line).to_string()));
+        }
+    });
+
+    PipeReader { handle, raw }
+}
+
+/// Cancels a running operation started with an `operation_id`. Returns false when
+/// nothing with that id is running (anymore).
+#[tauri::command]
+pub fn cancel_operation(operation_id: String) -> bool {
+    match operations().lock().get(&operation_id) {
+        Some(entry) => {
+            entry.cancelled.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
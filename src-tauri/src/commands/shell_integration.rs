@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::error::AppError;
+
+/// Longest OSC payload we buffer; anything longer isn't a shell-integration mark.
+const MAX_OSC_LEN: usize = 8192;
+const MAX_HISTORY: usize = 500;
+
+const ZSH_SCRIPT: &str = r#"# Autopilot shell integration for zsh; source from ~/.zshrc
+__autopilot_precmd() {
+  local code=$?
+  printf '\e]133;D;%s\a\e]7;file://%s%s\a\e]133;A\a' "$code" "$HOST" "$PWD"
+}
+__autopilot_preexec() {
+  local cmd=${1//\\/\\\\}
+  cmd=${cmd//;/\\x3b}
+  printf '\e]633;E;%s\a\e]133;C\a' "$cmd"
+}
+precmd_functions+=(__autopilot_precmd)
+preexec_functions+=(__autopilot_preexec)
+PS1=$PS1$'%{\e]133;B\a%}'
+"#;
+
+const BASH_SCRIPT: &str = r#"# Autopilot shell integration for bash 4.4+; source from ~/.bashrc
+__autopilot_precmd() {
+  local code=$?
+  printf '\e]133;D;%s\a\e]7;file://%s%s\a\e]133;A\a' "$code" "$HOSTNAME" "$PWD"
+}
+__autopilot_preexec() {
+  local cmd
+  cmd=$(HISTTIMEFORMAT= builtin history 1 | sed 's/^ *[0-9]* *//')
+  cmd=${cmd//\\/\\\\}
+  cmd=${cmd//;/\\x3b}
+  printf '\e]633;E;%s\a\e]133;C\a' "$cmd"
+}
+PROMPT_COMMAND="__autopilot_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+PS0='$(__autopilot_preexec)'"$PS0"
+PS1="$PS1"'\[\e]133;B\a\]'
+"#;
+
+const FISH_SCRIPT: &str = r#"# Autopilot shell integration for fish; source from ~/.config/fish/config.fish
+function __autopilot_preexec --on-event fish_preexec
+    set -l cmd (string replace -a '\\' '\\\\' -- $argv[1] | string replace -a ';' '\\x3b')
+    printf '\e]633;E;%s\a\e]133;C\a' "$cmd"
+end
+function __autopilot_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
+function __autopilot_prompt --on-event fish_prompt
+    printf '\e]7;file://%s%s\a\e]133;A\a' (hostname) "$PWD"
+end
+"#;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellEvent {
+    PromptStart,
+    PromptEnd,
+    /// Command line as reported by OSC 633;E
+    CommandLine(String),
+    CommandExecuted,
+    CommandFinished(Option<i32>),
+    Cwd(String),
+}
+
+#[derive(Default)]
+enum ParseState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Streaming parser for OSC 133 / 633 command marks and OSC 7 cwd reports.
+/// Sequences may be split across reads; other output is ignored.
+#[derive(Default)]
+pub struct OscParser {
+    state: ParseState,
+    buf: Vec<u8>,
+}
+
+impl OscParser {
+    pub fn feed(&mut self, data: &[u8]) -> Vec<ShellEvent> {
+        let mut events = Vec::new();
+
+        for &byte in data {
+            self.state = match (&self.state, byte) {
+                (ParseState::Ground, 0x1b) => ParseState::Escape,
+                (ParseState::Ground, _) => ParseState::Ground,
+                (ParseState::Escape, b']') => {
+                    self.buf.clear();
+                    ParseState::Osc
+                }
+                (ParseState::Escape, 0x1b) => ParseState::Escape,
+                (ParseState::Escape, _) => ParseState::Ground,
+                (ParseState::Osc, 0x07) => {
+                    events.extend(parse_osc(&self.buf));
+                    ParseState::Ground
+                }
+                (ParseState::Osc, 0x1b) => ParseState::OscEscape,
+                (ParseState::Osc, _) if self.buf.len() >= MAX_OSC_LEN => ParseState::Ground,
+                (ParseState::Osc, _) => {
+                    self.buf.push(byte);
+                    ParseState::Osc
+                }
+                (ParseState::OscEscape, b'\\') => {
+                    events.extend(parse_osc(&self.buf));
+                    ParseState::Ground
+                }
+                (ParseState::OscEscape, _) => ParseState::Ground,
+            };
+        }
+
+        events
+    }
+}
+
+fn parse_osc(payload: &[u8]) -> Option<ShellEvent> {
+    let payload = String::from_utf8_lossy(payload);
+    let (code, rest) = payload.split_once(';')?;
+
+    match code {
+        "7" => cwd_from_file_url(rest).map(ShellEvent::Cwd),
+        "133" | "633" => {
+            let (mark, args) = rest.split_once(';').unwrap_or((rest, ""));
+            match mark {
+                "A" => Some(ShellEvent::PromptStart),
+                "B" => Some(ShellEvent::PromptEnd),
+                "C" => Some(ShellEvent::CommandExecuted),
+                "D" => Some(ShellEvent::CommandFinished(
+                    args.split(';').next().and_then(|c| c.trim().parse().ok()),
+                )),
+                "E" if code == "633" => Some(ShellEvent::CommandLine(unescape_command(args))),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `file://host/some%20path` -> `/some path`. The host is ignored.
+fn cwd_from_file_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    Some(decode_escapes(path, '%', 2))
+}
+
+/// Command lines escape `\` as `\\` and `;` as `\x3b` (the VS Code convention).
+fn unescape_command(text: &str) -> String {
+    let text = text.split(';').next().unwrap_or_default();
+    decode_escapes(&text.replace("\\\\", "\\x5c"), '\\', 3)
+}
+
+/// Decodes `<prefix>XX` (or `<prefix>xXX` when `width` is 3) hex escapes,
+/// keeping anything that isn't a valid escape as-is.
+fn decode_escapes(text: &str, prefix: char, width: usize) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let end = i + 1 + width;
+        if bytes[i] == prefix as u8 && end <= bytes.len() {
+            let escape = std::str::from_utf8(&bytes[i + 1..end]).unwrap_or_default();
+            let hex = if width == 3 { escape.strip_prefix('x') } else { Some(escape) };
+            if let Some(value) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(value);
+                i += 1 + width;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandRecord {
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Command history of one terminal, built from the parsed shell events.
+#[derive(Default)]
+pub struct ShellTracker {
+    history: VecDeque<CommandRecord>,
+    running: Option<CommandRecord>,
+    pending_command: Option<String>,
+    cwd: Option<String>,
+}
+
+impl ShellTracker {
+    /// Treats `command` as already running, for terminals spawned with a command.
+    pub fn with_running(command: String, cwd: String, started_at: DateTime<Utc>) -> Self {
+        Self {
+            running: Some(CommandRecord {
+                command: Some(command),
+                cwd: Some(cwd.clone()),
+                started_at,
+                finished_at: None,
+                exit_code: None,
+                duration_ms: None,
+            }),
+            cwd: Some(cwd),
+            ..Default::default()
+        }
+    }
+
+    /// Applies an event; returns the record of a command that started or finished.
+    pub fn apply(&mut self, event: ShellEvent) -> Option<CommandRecord> {
+        let now = Utc::now();
+        match event {
+            ShellEvent::PromptStart | ShellEvent::PromptEnd => None,
+            ShellEvent::CommandLine(command) => {
+                self.pending_command = Some(command);
+                None
+            }
+            ShellEvent::CommandExecuted => {
+                let record = CommandRecord {
+                    command: self.pending_command.take(),
+                    cwd: self.cwd.clone(),
+                    started_at: now,
+                    finished_at: None,
+                    exit_code: None,
+                    duration_ms: None,
+                };
+                self.running = Some(record.clone());
+                Some(record)
+            }
+            ShellEvent::CommandFinished(exit_code) => {
+                // Shells also send D before the first prompt, when nothing ran
+                let mut record = self.running.take()?;
+                record.finished_at = Some(now);
+                record.exit_code = exit_code;
+                let elapsed = now - record.started_at;
+                record.duration_ms = Some(elapsed.num_milliseconds().max(0) as u64);
+
+                if self.history.len() == MAX_HISTORY {
+                    self.history.pop_front();
+                }
+                self.history.push_back(record.clone());
+                Some(record)
+            }
+            ShellEvent::Cwd(cwd) => {
+                self.cwd = Some(cwd);
+                None
+            }
+        }
+    }
+
+    pub fn running_command(&self) -> Option<&str> {
+        self.running.as_ref()?.command.as_deref()
+    }
+
+    pub fn history(&self) -> Vec<CommandRecord> {
+        self.history.iter().chain(self.running.iter()).cloned().collect()
+    }
+}
+
+/// Returns a snippet for the user's shell rc file that emits OSC 133 / 633 / 7 sequences.
+#[tauri::command]
+pub fn get_shell_integration_script(shell: String) -> Result<String, AppError> {
+    let name = shell.rsplit('/').next().unwrap_or(&shell).to_ascii_lowercase();
+    match name.as_str() {
+        "zsh" => Ok(ZSH_SCRIPT.to_string()),
+        "bash" => Ok(BASH_SCRIPT.to_string()),
+        "fish" => Ok(FISH_SCRIPT.to_string()),
+        _ => Err(AppError::InvalidInput(format!(
+            "No shell integration available for {}",
+            shell
+        ))),
+    }
+}
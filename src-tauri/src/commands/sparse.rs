@@ -0,0 +1,275 @@
+use git2::{Index, IndexEntryExtendedFlag, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::error::AppError;
+use super::git::{open_repo, run_git, run_git_long};
+
+const PROFILE_SECTION: &str = "autopilot.sparse";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SparseSpec {
+    /// Saved profile to start from, see `save_sparse_profile`
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Cone mode takes directories; non-cone mode takes gitignore-style patterns.
+    /// Inferred from the patterns when not set.
+    pub cone: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SparseProfile {
+    pub name: String,
+    pub patterns: Vec<String>,
+    pub cone: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SparseState {
+    pub enabled: bool,
+    pub cone: bool,
+    pub patterns: Vec<String>,
+}
+
+fn looks_like_cone(patterns: &[String]) -> bool {
+    patterns
+        .iter()
+        .all(|p| !p.starts_with('!') && !p.contains(['*', '?', '[']))
+}
+
+fn validate_profile_name(name: &str) -> Result<(), AppError> {
+    // Git config variable names must start with a letter
+    let starts_with_letter = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+    if !starts_with_letter || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid sparse profile name: {} (start with a letter, then use letters, digits and '-')",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn load_profiles(repo: &Repository) -> Result<Vec<SparseProfile>, AppError> {
+    let config = repo.config()?;
+    let pattern = format!("^{}\\.", PROFILE_SECTION.replace('.', "\\."));
+    let mut entries = config
+        .entries(Some(&pattern))?;
+
+    let mut profiles: Vec<SparseProfile> = Vec::new();
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        let (Some(key), Some(value)) = (entry.name(), entry.value()) else {
+            continue;
+        };
+        let name = key[PROFILE_SECTION.len() + 1..].to_string();
+
+        match profiles.iter_mut().find(|p| p.name == name) {
+            Some(profile) => profile.patterns.push(value.to_string()),
+            None => profiles.push(SparseProfile {
+                name,
+                patterns: vec![value.to_string()],
+                cone: false,
+            }),
+        }
+    }
+
+    for profile in &mut profiles {
+        profile.cone = looks_like_cone(&profile.patterns);
+    }
+
+    Ok(profiles)
+}
+
+/// Merges a saved profile with any extra patterns and settles on cone mode.
+pub fn resolve_sparse_spec(repo: &Repository, spec: &SparseSpec) -> Result<(bool, Vec<String>), AppError> {
+    let mut patterns = Vec::new();
+
+    if let Some(name) = &spec.profile {
+        let profile = load_profiles(repo)?
+            .into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Sparse profile not found: {}", name))?;
+        patterns.extend(profile.patterns);
+    }
+
+    patterns.extend(
+        spec.patterns
+            .iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty()),
+    );
+
+    if patterns.is_empty() {
+        return Err(AppError::InvalidInput("Sparse checkout needs at least one pattern".to_string()));
+    }
+
+    let cone = spec.cone.unwrap_or_else(|| looks_like_cone(&patterns));
+    Ok((cone, patterns))
+}
+
+/// Sets the sparse-checkout patterns, which also updates the working tree.
+pub fn apply_sparse_patterns(
+    worktree_path: &Path,
+    cone: bool,
+    patterns: &[String],
+    operation_id: Option<&str>,
+) -> Result<(), AppError> {
+    let dir = worktree_path.to_string_lossy().to_string();
+    let mut args = vec!["sparse-checkout", "set", if cone { "--cone" } else { "--no-cone" }];
+    args.extend(patterns.iter().map(String::as_str));
+    run_git_long(&dir, &args, operation_id)?;
+    Ok(())
+}
+
+/// Sparse-excluded files are missing from the working tree but still in the
+/// index with the skip-worktree bit, so libgit2 reports them as deleted.
+pub fn is_skip_worktree(index: &Index, path: &str) -> bool {
+    index
+        .get_path(Path::new(path), 0)
+        .map(|entry| IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended).is_skip_worktree())
+        .unwrap_or(false)
+}
+
+fn read_sparse_state(worktree_path: &Path) -> SparseState {
+    let dir = worktree_path.to_string_lossy().to_string();
+    let enabled = run_git(&dir, &["config", "--bool", "core.sparseCheckout"])
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return SparseState {
+            enabled: false,
+            cone: false,
+            patterns: Vec::new(),
+        };
+    }
+
+    let cone = run_git(&dir, &["config", "--bool", "core.sparseCheckoutCone"])
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false);
+    let patterns = run_git(&dir, &["sparse-checkout", "list"])
+        .map(|out| out.lines().map(String::from).filter(|l| !l.is_empty()).collect())
+        .unwrap_or_default();
+
+    SparseState {
+        enabled,
+        cone,
+        patterns,
+    }
+}
+
+/// Directories to watch for a cone-mode sparse worktree, or `None` when the
+/// whole tree is checked out. Top-level files are always part of a cone.
+pub fn sparse_watch_dirs(worktree_path: &Path) -> Option<Vec<PathBuf>> {
+    let state = read_sparse_state(worktree_path);
+    if !state.enabled || !state.cone {
+        return None;
+    }
+
+    Some(
+        state
+            .patterns
+            .iter()
+            .map(|dir| worktree_path.join(dir.trim_matches('/')))
+            .collect(),
+    )
+}
+
+#[tauri::command]
+pub fn list_sparse_profiles(repo_path: String) -> Result<Vec<SparseProfile>, AppError> {
+    let repo = open_repo(&repo_path)?;
+    load_profiles(&repo)
+}
+
+#[tauri::command]
+pub fn save_sparse_profile(
+    repo_path: String,
+    name: String,
+    patterns: Vec<String>,
+) -> Result<SparseProfile, AppError> {
+    validate_profile_name(&name)?;
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if patterns.is_empty() {
+        return Err(AppError::InvalidInput("Sparse profile needs at least one pattern".to_string()));
+    }
+
+    let repo = open_repo(&repo_path)?;
+    let mut config = repo
+        .config()
+        .and_then(|c| c.open_level(git2::ConfigLevel::Local))?;
+
+    let key = format!("{}.{}", PROFILE_SECTION, name);
+    match config.remove_multivar(&key, ".*") {
+        Ok(()) => {}
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    for pattern in &patterns {
+        // "a^" never matches, so every value is appended
+        config
+            .set_multivar(&key, "a^", pattern)?;
+    }
+
+    Ok(SparseProfile {
+        cone: looks_like_cone(&patterns),
+        name,
+        patterns,
+    })
+}
+
+#[tauri::command]
+pub fn delete_sparse_profile(repo_path: String, name: String) -> Result<(), AppError> {
+    validate_profile_name(&name)?;
+    let repo = open_repo(&repo_path)?;
+    let mut config = repo
+        .config()
+        .and_then(|c| c.open_level(git2::ConfigLevel::Local))?;
+
+    match config.remove_multivar(&format!("{}.{}", PROFILE_SECTION, name), ".*") {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub fn get_sparse_checkout(worktree_path: String) -> Result<SparseState, AppError> {
+    Ok(read_sparse_state(Path::new(&worktree_path)))
+}
+
+#[tauri::command]
+pub async fn set_sparse_checkout(
+    worktree_path: String,
+    spec: SparseSpec,
+    operation_id: Option<String>,
+) -> Result<SparseState, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let repo = open_repo(&worktree_path)?;
+        let (cone, patterns) = resolve_sparse_spec(&repo, &spec)?;
+        let path = Path::new(&worktree_path);
+        apply_sparse_patterns(path, cone, &patterns, operation_id.as_deref())?;
+        Ok::<SparseState, AppError>(read_sparse_state(path))
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn disable_sparse_checkout(
+    worktree_path: String,
+    operation_id: Option<String>,
+) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        // Checks out every file that was excluded, which can take a while
+        run_git_long(
+            &worktree_path,
+            &["sparse-checkout", "disable"],
+            operation_id.as_deref(),
+        )?;
+        Ok::<(), AppError>(())
+    })
+    .await?
+}
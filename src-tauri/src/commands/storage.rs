@@ -0,0 +1,41 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use super::error::AppError;
+
+fn data_file(app: &AppHandle, file_name: &str) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Cannot resolve app data directory: {}", e))?;
+    Ok(dir.join(file_name))
+}
+
+/// Reads a JSON file from the app data directory, falling back to the
+/// default value when it doesn't exist yet.
+pub fn load_json<T: DeserializeOwned + Default>(app: &AppHandle, file_name: &str) -> Result<T, AppError> {
+    let path = data_file(app, file_name)?;
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| AppError::Other(format!("Failed to parse {}: {}", file_name, e)))
+}
+
+/// Writes a JSON file to the app data directory via a temp file, so a crash
+/// mid-write never leaves a truncated file behind.
+pub fn save_json<T: Serialize>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), AppError> {
+    let path = data_file(app, file_name)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let contents = serde_json::to_string_pretty(value)?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, contents)?;
+    Ok(std::fs::rename(&tmp_path, &path)?)
+}
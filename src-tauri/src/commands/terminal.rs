@@ -1,24 +1,166 @@
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use git2::Repository;
 use parking_lot::Mutex;
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use super::attention::{self, AgentNeedsAttention, AttentionDetector, RECENT_OUTPUT_BYTES};
+use super::error::AppError;
+use super::recording::{start_recording, CastRecorder};
+use super::shell_integration::{CommandRecord, OscParser, ShellEvent, ShellTracker};
+use super::transcript::{search_output, OutputBuffer, OutputMatch, TranscriptFormat};
 use crate::AppState;
 
 pub struct TerminalSession {
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
     child: Box<dyn portable_pty::Child + Send + Sync>,
     master: Arc<Mutex<Box<dyn portable_pty::MasterPty + Send>>>,
+    info: Arc<Mutex<TerminalInfo>>,
+    shell: Arc<Mutex<ShellTracker>>,
+    output: Arc<Mutex<OutputBuffer>>,
+    recorder: Option<Arc<Mutex<CastRecorder>>>,
+}
+
+impl TerminalSession {
+    pub fn info(&self) -> TerminalInfo {
+        self.info.lock().clone()
+    }
+}
+
+/// Metadata about a terminal session. Sessions stay listed after their
+/// process exits, until `close_terminal` is called.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalInfo {
+    pub terminal_id: String,
+    /// Kept up to date from OSC 7 reports when shell integration is active
+    pub cwd: String,
+    /// Working directory of the worktree `cwd` belongs to, if any
+    pub worktree: Option<String>,
+    pub title: Option<String>,
+    /// Command the terminal was spawned with; `None` for a plain shell
+    pub command: Option<String>,
+    pub pid: Option<u32>,
+    pub started_at: DateTime<Utc>,
+    pub alive: bool,
+    pub exit_code: Option<u32>,
+    pub exited_at: Option<DateTime<Utc>>,
+    pub last_activity: DateTime<Utc>,
+    /// Exit code of `command`, once it has finished
+    pub command_exit_code: Option<i32>,
+    /// Whether the shell emits OSC 133 command marks
+    pub shell_integration: bool,
+    /// An agent prompt is waiting for an answer, see `attention.rs`
+    pub needs_attention: bool,
+}
+
+/// Output is coalesced for this long before being sent to the frontend
+const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_millis(8);
+/// A batch this large is sent right away
+const OUTPUT_MAX_BATCH: usize = 64 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputEncoding {
+    /// Text, with multi-byte characters never split across events
+    #[default]
+    Utf8,
+    /// Raw PTY bytes, base64 encoded
+    Base64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TerminalOutput {
     pub terminal_id: String,
     pub data: String,
+    pub encoding: OutputEncoding,
+}
+
+/// Decodes UTF-8 across reads, carrying an incomplete trailing sequence over
+/// to the next chunk instead of turning it into U+FFFD.
+#[derive(Default)]
+struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    fn decode(&mut self, data: &[u8]) -> String {
+        self.pending.extend_from_slice(data);
+        let mut out = String::with_capacity(self.pending.len());
+        let mut rest = self.pending.as_slice();
+
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    out.push_str(valid);
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        // Incomplete sequence at the end, wait for more bytes
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.pending = rest.to_vec();
+        out
+    }
+
+    /// Whatever is left when the PTY closes can't be completed anymore.
+    fn finish(&mut self) -> String {
+        let rest = String::from_utf8_lossy(&self.pending).to_string();
+        self.pending.clear();
+        rest
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalClosed {
+    pub terminal_id: String,
+    pub exit_code: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalCommandStarted {
+    pub terminal_id: String,
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+}
+
+/// Emitted when a command finishes, either the one `spawn_terminal_with_command`
+/// ran (not on Windows) or one run in a shell with shell integration.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalCommandFinished {
+    pub terminal_id: String,
+    pub command: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TerminalCwdChanged {
+    pub terminal_id: String,
+    pub cwd: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,57 +181,261 @@ fn should_wrap_shell(shell: &str) -> bool {
     s.ends_with("/zsh") || s.ends_with("/bash")
 }
 
-#[tauri::command]
-pub fn spawn_terminal(
-    app: AppHandle,
-    state: State<'_, AppState>,
-    cwd: String,
-    cols: u16,
-    rows: u16,
-    is_dark_mode: bool,
-) -> Result<TerminalSpawnResult, String> {
-    let terminal_id = Uuid::new_v4().to_string();
+pub fn pty_size(cols: u16, rows: u16) -> PtySize {
+    PtySize {
+        rows,
+        cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
 
-    let pty_system = native_pty_system();
+fn worktree_for_cwd(cwd: &str) -> Option<String> {
+    let repo = Repository::discover(cwd).ok()?;
+    let workdir = repo.workdir()?;
+    Some(workdir.to_string_lossy().trim_end_matches('/').to_string())
+}
 
-    let pair = pty_system
-        .openpty(PtySize {
-            rows,
-            cols,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|e| e.to_string())?;
+/// The PTY closes slightly before the child is reaped, so poll briefly for its status.
+fn wait_for_exit(
+    terminals: &Mutex<HashMap<String, TerminalSession>>,
+    terminal_id: &str,
+) -> Option<u32> {
+    for _ in 0..20 {
+        {
+            let mut terminals = terminals.lock();
+            let session = terminals.get_mut(terminal_id)?;
+            if let Ok(Some(status)) = session.child.try_wait() {
+                return Some(status.exit_code());
+            }
+        }
+        thread::sleep(Duration::from_millis(25));
+    }
+    None
+}
 
-    let shell = get_shell();
-    let mut cmd = if !cfg!(target_os = "windows") && should_wrap_shell(&shell) {
-        let mut c = CommandBuilder::new(&shell);
-        c.arg("-li");
-        c
-    } else {
-        CommandBuilder::new(&shell)
+fn handle_shell_event(
+    app: &AppHandle,
+    terminal_id: &str,
+    info: &Mutex<TerminalInfo>,
+    shell: &Mutex<ShellTracker>,
+    event: ShellEvent,
+    awaiting_wrapped_command: &mut bool,
+) {
+    if let ShellEvent::Cwd(cwd) = &event {
+        let mut info = info.lock();
+        if info.cwd != *cwd {
+            info.cwd = cwd.clone();
+            info.worktree = worktree_for_cwd(cwd);
+            let _ = app.emit(
+                "terminal-cwd-changed",
+                TerminalCwdChanged {
+                    terminal_id: terminal_id.to_string(),
+                    cwd: cwd.clone(),
+                },
+            );
+        }
+    }
+
+    let finished = matches!(event, ShellEvent::CommandFinished(_));
+    let Some(record) = shell.lock().apply(event) else {
+        return;
     };
-    cmd.cwd(&cwd);
 
-    if !cfg!(target_os = "windows") {
-        cmd.env("TERM", "xterm-256color");
-        cmd.env("COLORTERM", "truecolor");
-        cmd.env("TERM_PROGRAM", "Autopilot");
-        // COLORFGBG helps TUI apps detect light/dark mode (xterm-256 color indices)
-        // "231;16" = white fg on black bg (dark mode)
-        // "16;231" = black fg on white bg (light mode)
-        cmd.env("COLORFGBG", if is_dark_mode { "231;16" } else { "16;231" });
+    if !finished {
+        info.lock().shell_integration = true;
+        let _ = app.emit(
+            "terminal-command-started",
+            TerminalCommandStarted {
+                terminal_id: terminal_id.to_string(),
+                command: record.command,
+                cwd: record.cwd,
+            },
+        );
+        return;
+    }
+
+    if std::mem::take(awaiting_wrapped_command) {
+        info.lock().command_exit_code = record.exit_code;
+    }
+    let _ = app.emit(
+        "terminal-command-finished",
+        TerminalCommandFinished {
+            terminal_id: terminal_id.to_string(),
+            command: record.command,
+            exit_code: record.exit_code,
+            duration_ms: record.duration_ms.unwrap_or_default(),
+        },
+    );
+}
+
+struct LaunchOptions {
+    cwd: String,
+    command: Option<String>,
+    title: Option<String>,
+    size: PtySize,
+    encoding: OutputEncoding,
+}
+
+/// Turns batches of PTY output into `terminal-output` and shell-integration events.
+struct OutputPump {
+    app: AppHandle,
+    terminal_id: String,
+    encoding: OutputEncoding,
+    decoder: Utf8Decoder,
+    parser: OscParser,
+    info: Arc<Mutex<TerminalInfo>>,
+    shell: Arc<Mutex<ShellTracker>>,
+    output: Arc<Mutex<OutputBuffer>>,
+    recorder: Option<Arc<Mutex<CastRecorder>>>,
+    attention: AttentionDetector,
+    awaiting_wrapped_command: bool,
+}
+
+impl OutputPump {
+    fn flush(&mut self, batch: &[u8], closing: bool) {
+        let mut text = self.decoder.decode(batch);
+        if closing {
+            text.push_str(&self.decoder.finish());
+        }
+        if !text.is_empty() {
+            self.attention.output_received();
+            self.info.lock().needs_attention = false;
+        }
+        self.output.lock().push_output(&text);
+        if let Some(recorder) = &self.recorder {
+            let mut recorder = recorder.lock();
+            recorder.output(&text);
+            if closing {
+                recorder.finish();
+            }
+        }
+
+        let data = match self.encoding {
+            OutputEncoding::Utf8 => text,
+            OutputEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(batch),
+        };
+        if !data.is_empty() {
+            let _ = self.app.emit(
+                "terminal-output",
+                TerminalOutput {
+                    terminal_id: self.terminal_id.clone(),
+                    data,
+                    encoding: self.encoding,
+                },
+            );
+        }
+
+        // Marks are handled after the output they were part of has been sent
+        for event in self.parser.feed(batch) {
+            handle_shell_event(
+                &self.app,
+                &self.terminal_id,
+                &self.info,
+                &self.shell,
+                event,
+                &mut self.awaiting_wrapped_command,
+            );
+        }
     }
+}
+
+impl OutputPump {
+    /// Runs once output has gone quiet, to spot an agent waiting on a prompt.
+    fn check_attention(&mut self) {
+        let (command, worktree) = {
+            let info = self.info.lock();
+            (info.command.clone(), info.worktree.clone())
+        };
+        // In a plain shell the agent is whatever command is running right now
+        let command = command.or_else(|| self.shell.lock().running_command().map(String::from));
+        let agent = command.as_deref().and_then(attention::agent_name);
+
+        let recent = self.output.lock().tail(RECENT_OUTPUT_BYTES);
+        let Some(line) = self.attention.check(agent.as_deref(), &recent) else {
+            return;
+        };
+
+        self.info.lock().needs_attention = true;
+        self.attention.raise(
+            &self.app,
+            AgentNeedsAttention {
+                terminal_id: self.terminal_id.clone(),
+                worktree,
+                agent,
+                line,
+            },
+        );
+    }
+}
+
+/// Opens a PTY, runs `cmd` in it and streams its output as `terminal-output` events.
+fn launch_session(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cmd: CommandBuilder,
+    options: LaunchOptions,
+) -> Result<TerminalSpawnResult, AppError> {
+    let LaunchOptions {
+        cwd,
+        command,
+        title,
+        size,
+        encoding,
+    } = options;
+    let terminal_id = Uuid::new_v4().to_string();
+
+    let pty_system = native_pty_system();
+
+    let pair = pty_system.openpty(size).map_err(|e| e.to_string())?;
 
     let child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
 
     let reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
     let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
 
+    let now = Utc::now();
+    let info = Arc::new(Mutex::new(TerminalInfo {
+        terminal_id: terminal_id.clone(),
+        worktree: worktree_for_cwd(&cwd),
+        cwd: cwd.clone(),
+        title,
+        command: command.clone(),
+        pid: child.process_id(),
+        started_at: now,
+        alive: true,
+        exit_code: None,
+        exited_at: None,
+        last_activity: now,
+        command_exit_code: None,
+        shell_integration: false,
+        needs_attention: false,
+    }));
+
+    let shell = Arc::new(Mutex::new(match &command {
+        Some(command) => ShellTracker::with_running(command.clone(), cwd.clone(), now),
+        None => ShellTracker::default(),
+    }));
+
+    let output = Arc::new(Mutex::new(OutputBuffer::new(size.cols, size.rows)));
+    let recorder = start_recording(
+        &app,
+        info.lock().worktree.as_deref(),
+        &terminal_id,
+        size.cols,
+        size.rows,
+        command.as_deref(),
+    )
+    .map(|recorder| Arc::new(Mutex::new(recorder)));
+
     let session = TerminalSession {
         writer: Arc::new(Mutex::new(writer)),
         child,
         master: Arc::new(Mutex::new(pair.master)),
+        info: info.clone(),
+        shell: shell.clone(),
+        output: output.clone(),
+        recorder: recorder.clone(),
     };
 
     state.terminals.lock().insert(terminal_id.clone(), session);
@@ -98,6 +444,8 @@ pub fn spawn_terminal(
     let app_clone = app.clone();
     let state_terminals = state.terminals.clone();
 
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+
     thread::spawn(move || {
         let mut reader = reader;
         let mut buf = [0u8; 4096];
@@ -106,30 +454,131 @@ pub fn spawn_terminal(
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
-                    let data = String::from_utf8_lossy(&buf[..n]).to_string();
-                    let output = TerminalOutput {
-                        terminal_id: tid.clone(),
-                        data,
-                    };
-                    let _ = app_clone.emit("terminal-output", output);
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
                 }
                 Err(_) => break,
             }
         }
+    });
+
+    thread::spawn(move || {
+        let mut pump = OutputPump {
+            app: app_clone.clone(),
+            terminal_id: tid.clone(),
+            encoding,
+            decoder: Utf8Decoder::default(),
+            parser: OscParser::default(),
+            info: info.clone(),
+            shell,
+            output,
+            recorder,
+            attention: AttentionDetector::new(&attention::load_settings(&app_clone)),
+            awaiting_wrapped_command: command.is_some(),
+        };
+        let mut batch: Vec<u8> = Vec::new();
+        let mut deadline: Option<Instant> = None;
+
+        loop {
+            let received = match (deadline, pump.attention.pending_check()) {
+                (Some(deadline), _) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                (None, Some(idle_in)) => rx.recv_timeout(idle_in),
+                (None, None) => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(chunk) => {
+                    info.lock().last_activity = Utc::now();
+                    batch.extend_from_slice(&chunk);
+                    deadline.get_or_insert_with(|| Instant::now() + OUTPUT_FLUSH_INTERVAL);
+                    if batch.len() < OUTPUT_MAX_BATCH {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) if deadline.is_none() => {
+                    pump.check_attention();
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    pump.flush(&batch, true);
+                    break;
+                }
+            }
+            pump.flush(&batch, false);
+            batch.clear();
+            deadline = None;
+        }
 
-        state_terminals.lock().remove(&tid);
-        let _ = app_clone.emit("terminal-closed", tid);
+        let exit_code = wait_for_exit(&state_terminals, &tid);
+        {
+            let mut info = info.lock();
+            info.alive = false;
+            info.exit_code = exit_code;
+            info.exited_at = Some(Utc::now());
+        }
+        let _ = app_clone.emit(
+            "terminal-closed",
+            TerminalClosed {
+                terminal_id: tid,
+                exit_code,
+            },
+        );
     });
 
     Ok(TerminalSpawnResult { terminal_id })
 }
 
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_terminal(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cwd: String,
+    cols: u16,
+    rows: u16,
+    is_dark_mode: bool,
+    title: Option<String>,
+    output_encoding: Option<OutputEncoding>,
+) -> Result<TerminalSpawnResult, AppError> {
+    let shell = get_shell();
+    let mut cmd = if !cfg!(target_os = "windows") && should_wrap_shell(&shell) {
+        let mut c = CommandBuilder::new(&shell);
+        c.arg("-li");
+        c
+    } else {
+        CommandBuilder::new(&shell)
+    };
+    cmd.cwd(&cwd);
+
+    if !cfg!(target_os = "windows") {
+        cmd.env("TERM", "xterm-256color");
+        cmd.env("COLORTERM", "truecolor");
+        cmd.env("TERM_PROGRAM", "Autopilot");
+        // COLORFGBG helps TUI apps detect light/dark mode (xterm-256 color indices)
+        // "231;16" = white fg on black bg (dark mode)
+        // "16;231" = black fg on white bg (light mode)
+        cmd.env("COLORFGBG", if is_dark_mode { "231;16" } else { "16;231" });
+    }
+
+    let options = LaunchOptions {
+        cwd,
+        command: None,
+        title,
+        size: pty_size(cols, rows),
+        encoding: output_encoding.unwrap_or_default(),
+    };
+    launch_session(app, state, cmd, options)
+}
+
 #[tauri::command]
 pub fn write_to_terminal(
     state: State<'_, AppState>,
     terminal_id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let terminals = state.terminals.lock();
     let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
 
@@ -138,6 +587,9 @@ pub fn write_to_terminal(
         .write_all(data.as_bytes())
         .map_err(|e| e.to_string())?;
     writer.flush().map_err(|e| e.to_string())?;
+    if let Some(recorder) = &session.recorder {
+        recorder.lock().input(&data);
+    }
 
     Ok(())
 }
@@ -148,7 +600,7 @@ pub fn resize_terminal(
     terminal_id: String,
     cols: u16,
     rows: u16,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let terminals = state.terminals.lock();
     let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
 
@@ -161,14 +613,22 @@ pub fn resize_terminal(
             pixel_height: 0,
         })
         .map_err(|e| e.to_string())?;
+    session.output.lock().push_resize(cols, rows);
+    if let Some(recorder) = &session.recorder {
+        recorder.lock().resize(cols, rows);
+    }
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn close_terminal(state: State<'_, AppState>, terminal_id: String) -> Result<(), String> {
+pub fn close_terminal(state: State<'_, AppState>, terminal_id: String) -> Result<(), AppError> {
     let mut terminals = state.terminals.lock();
     if let Some(session) = terminals.remove(&terminal_id) {
+        // An exited session's pid may already belong to another process
+        if !session.info.lock().alive {
+            return Ok(());
+        }
         if let Some(pid) = session.child.process_id() {
             #[cfg(unix)]
             {
@@ -189,9 +649,84 @@ pub fn close_terminal(state: State<'_, AppState>, terminal_id: String) -> Result
     Ok(())
 }
 
+/// Lists all terminal sessions, including ones whose process has exited.
+#[tauri::command]
+pub fn list_terminals(state: State<'_, AppState>) -> Vec<TerminalInfo> {
+    let mut terminals: Vec<TerminalInfo> = state
+        .terminals
+        .lock()
+        .values()
+        .map(|session| session.info.lock().clone())
+        .collect();
+    terminals.sort_by_key(|t| t.started_at);
+    terminals
+}
+
+#[tauri::command]
+pub fn set_terminal_title(
+    state: State<'_, AppState>,
+    terminal_id: String,
+    title: Option<String>,
+) -> Result<(), AppError> {
+    let terminals = state.terminals.lock();
+    let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
+    session.info.lock().title = title;
+    Ok(())
+}
+
+/// Commands run in the terminal, oldest first. Needs shell integration, except
+/// for the command a terminal was spawned with.
+#[tauri::command]
+pub fn get_terminal_history(
+    state: State<'_, AppState>,
+    terminal_id: String,
+) -> Result<Vec<CommandRecord>, AppError> {
+    let terminals = state.terminals.lock();
+    let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
+    let history = session.shell.lock().history();
+    Ok(history)
+}
+
+/// Searches the terminal's buffered output (escape sequences stripped) with a regex.
+#[tauri::command]
+pub fn search_terminal_output(
+    state: State<'_, AppState>,
+    terminal_id: String,
+    pattern: String,
+    case_insensitive: Option<bool>,
+    context: Option<usize>,
+) -> Result<Vec<OutputMatch>, AppError> {
+    let text = {
+        let terminals = state.terminals.lock();
+        let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
+        let text = session.output.lock().raw_text();
+        text
+    };
+    search_output(&text, &pattern, case_insensitive.unwrap_or(false), context.unwrap_or(2))
+}
+
+/// Writes the terminal's buffered output to `path` as text, HTML or asciicast.
+#[tauri::command]
+pub fn export_terminal_transcript(
+    state: State<'_, AppState>,
+    terminal_id: String,
+    format: TranscriptFormat,
+    path: String,
+) -> Result<String, AppError> {
+    let contents = {
+        let terminals = state.terminals.lock();
+        let session = terminals.get(&terminal_id).ok_or("Terminal not found")?;
+        let contents = session.output.lock().render(format);
+        contents
+    };
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(path)
+}
+
 /// Spawns a terminal that runs a specific command instead of a shell.
 /// The terminal stays open after the command completes so user can see output.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_terminal_with_command(
     app: AppHandle,
     state: State<'_, AppState>,
@@ -201,20 +736,9 @@ pub fn spawn_terminal_with_command(
     cols: u16,
     rows: u16,
     is_dark_mode: bool,
-) -> Result<TerminalSpawnResult, String> {
-    let terminal_id = Uuid::new_v4().to_string();
-
-    let pty_system = native_pty_system();
-
-    let pair = pty_system
-        .openpty(PtySize {
-            rows,
-            cols,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|e| e.to_string())?;
-
+    title: Option<String>,
+    output_encoding: Option<OutputEncoding>,
+) -> Result<TerminalSpawnResult, AppError> {
     // Run the command inside a shell so it has proper environment
     let shell = get_shell();
     let mut cmd = CommandBuilder::new(&shell);
@@ -232,9 +756,13 @@ pub fn spawn_terminal_with_command(
         cmd.arg(&full_command);
     } else {
         cmd.arg("-c");
-        // Run command, then start interactive shell so user can continue working
+        // Run command, report its exit code with a marker the reader thread picks up,
+        // then start interactive shell so user can continue working
         // Use ${SHELL:-/bin/bash} as fallback if $SHELL is unset
-        cmd.arg(format!("{}; exec ${{SHELL:-/bin/bash}}", full_command));
+        cmd.arg(format!(
+            "{}; printf '\\033]133;D;%d\\007' \"$?\"; exec ${{SHELL:-/bin/bash}}",
+            full_command
+        ));
     }
 
     cmd.cwd(&cwd);
@@ -246,45 +774,48 @@ pub fn spawn_terminal_with_command(
         cmd.env("COLORFGBG", if is_dark_mode { "231;16" } else { "16;231" });
     }
 
-    let child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
-
-    let reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
-    let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
-
-    let session = TerminalSession {
-        writer: Arc::new(Mutex::new(writer)),
-        child,
-        master: Arc::new(Mutex::new(pair.master)),
+    let options = LaunchOptions {
+        cwd,
+        command: Some(full_command),
+        title,
+        size: pty_size(cols, rows),
+        encoding: output_encoding.unwrap_or_default(),
     };
+    launch_session(app, state, cmd, options)
+}
 
-    state.terminals.lock().insert(terminal_id.clone(), session);
-
-    let tid = terminal_id.clone();
-    let app_clone = app.clone();
-    let state_terminals = state.terminals.clone();
 
-    thread::spawn(move || {
-        let mut reader = reader;
-        let mut buf = [0u8; 4096];
-
-        loop {
-            match reader.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    let data = String::from_utf8_lossy(&buf[..n]).to_string();
-                    let output = TerminalOutput {
-                        terminal_id: tid.clone(),
-                        data,
-                    };
-                    let _ = app_clone.emit("terminal-output", output);
-                }
-                Err(_) => break,
-            }
-        }
+/// Runs `program` directly in a PTY, without a shell around it, so the
+/// terminal's exit code is the program's own.
+pub fn spawn_program(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cwd: String,
+    program: &str,
+    args: &[String],
+    title: Option<String>,
+    size: PtySize,
+) -> Result<TerminalSpawnResult, AppError> {
+    let mut cmd = CommandBuilder::new(program);
+    cmd.args(args);
+    cmd.cwd(&cwd);
 
-        state_terminals.lock().remove(&tid);
-        let _ = app_clone.emit("terminal-closed", tid);
-    });
+    if !cfg!(target_os = "windows") {
+        cmd.env("TERM", "xterm-256color");
+        cmd.env("COLORTERM", "truecolor");
+        cmd.env("TERM_PROGRAM", "Autopilot");
+    }
 
-    Ok(TerminalSpawnResult { terminal_id })
+    let command = std::iter::once(program.to_string())
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let options = LaunchOptions {
+        cwd,
+        command: Some(command),
+        title,
+        size,
+        encoding: OutputEncoding::Utf8,
+    };
+    launch_session(app, state, cmd, options)
 }
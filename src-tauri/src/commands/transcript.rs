@@ -0,0 +1,352 @@
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use super::error::AppError;
+
+/// Output kept per terminal for search and export; older output is dropped first.
+const MAX_BUFFER_BYTES: usize = 2 * 1024 * 1024;
+const MAX_SEARCH_RESULTS: usize = 500;
+
+/// CSI, OSC and two-byte escape sequences
+static ANSI_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)?|\x1b[@-Z\\-_]").unwrap()
+});
+
+const ANSI_COLORS: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+    "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// Plain text with escape sequences stripped
+    Text,
+    /// HTML that keeps SGR colours and bold
+    Html,
+    /// asciicast v2, replayable with `asciinema play`
+    Cast,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutputMatch {
+    /// 1-based line number in the stripped output
+    pub line_number: usize,
+    pub line: String,
+    /// Byte range of the match within `line`
+    pub start: usize,
+    pub end: usize,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// A timestamped asciicast event: "o" output, "i" input or "r" resize.
+#[derive(Debug, Clone)]
+pub struct CastEvent {
+    pub time: f64,
+    pub kind: &'static str,
+    pub data: String,
+}
+
+/// Rolling buffer of a terminal's output with the timing needed for `.cast` export.
+pub struct OutputBuffer {
+    started_at: DateTime<Utc>,
+    started: Instant,
+    cols: u16,
+    rows: u16,
+    events: VecDeque<CastEvent>,
+    bytes: usize,
+}
+
+impl OutputBuffer {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: Instant::now(),
+            cols,
+            rows,
+            events: VecDeque::new(),
+            bytes: 0,
+        }
+    }
+
+    pub fn push_output(&mut self, data: &str) {
+        if data.is_empty() {
+            return;
+        }
+        self.bytes += data.len();
+        self.events.push_back(CastEvent {
+            time: self.started.elapsed().as_secs_f64(),
+            kind: "o",
+            data: data.to_string(),
+        });
+
+        while self.bytes > MAX_BUFFER_BYTES {
+            match self.events.pop_front() {
+                Some(event) => self.bytes -= event.data.len(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn push_resize(&mut self, cols: u16, rows: u16) {
+        self.events.push_back(CastEvent {
+            time: self.started.elapsed().as_secs_f64(),
+            kind: "r",
+            data: format!("{}x{}", cols, rows),
+        });
+    }
+
+    /// Roughly the last `max_bytes` of output, in whole chunks.
+    pub fn tail(&self, max_bytes: usize) -> String {
+        let mut chunks = Vec::new();
+        let mut bytes = 0;
+        for event in self.events.iter().rev().filter(|e| e.kind == "o") {
+            if bytes >= max_bytes {
+                break;
+            }
+            bytes += event.data.len();
+            chunks.push(event.data.as_str());
+        }
+        chunks.into_iter().rev().collect()
+    }
+
+    pub fn raw_text(&self) -> String {
+        self.events
+            .iter()
+            .filter(|e| e.kind == "o")
+            .map(|e| e.data.as_str())
+            .collect()
+    }
+
+    pub fn render(&self, format: TranscriptFormat) -> String {
+        match format {
+            TranscriptFormat::Text => strip_ansi(&self.raw_text()),
+            TranscriptFormat::Html => ansi_to_html(&self.raw_text()),
+            TranscriptFormat::Cast => {
+                let mut out = cast_header(self.cols, self.rows, self.started_at, None);
+                out.push('\n');
+                // Timing restarts at the oldest event still in the buffer
+                let offset = self.events.front().map(|e| e.time).unwrap_or_default();
+                for event in &self.events {
+                    out.push_str(&cast_event_line(event.time - offset, event.kind, &event.data));
+                    out.push('\n');
+                }
+                out
+            }
+        }
+    }
+}
+
+pub fn cast_header(cols: u16, rows: u16, started_at: DateTime<Utc>, title: Option<&str>) -> String {
+    let mut header = serde_json::json!({
+        "version": 2,
+        "width": cols,
+        "height": rows,
+        "timestamp": started_at.timestamp(),
+        "env": { "TERM": "xterm-256color" },
+    });
+    if let Some(title) = title {
+        header["title"] = title.into();
+    }
+    header.to_string()
+}
+
+pub fn cast_event_line(time: f64, kind: &str, data: &str) -> String {
+    serde_json::json!([(time * 1_000_000.0).round() / 1_000_000.0, kind, data]).to_string()
+}
+
+/// Removes escape sequences and resolves carriage returns the way a terminal
+/// would display them, so progress bars leave only their final state.
+pub fn strip_ansi(text: &str) -> String {
+    let stripped = ANSI_RE.replace_all(text, "");
+    stripped
+        .split('\n')
+        .map(|line| {
+            let line = line.trim_end_matches('\r');
+            line.rsplit('\r').next().unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn search_output(
+    text: &str,
+    pattern: &str,
+    case_insensitive: bool,
+    context: usize,
+) -> Result<Vec<OutputMatch>, AppError> {
+    let re = RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    let stripped = strip_ansi(text);
+    let lines: Vec<&str> = stripped.lines().collect();
+    let mut matches = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        for m in re.find_iter(line) {
+            matches.push(OutputMatch {
+                line_number: index + 1,
+                line: line.to_string(),
+                start: m.start(),
+                end: m.end(),
+                before: lines[index.saturating_sub(context)..index]
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect(),
+                after: lines[index + 1..(index + 1 + context).min(lines.len())]
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect(),
+            });
+            if matches.len() == MAX_SEARCH_RESULTS {
+                return Ok(matches);
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+fn color_256(n: u16) -> String {
+    match n {
+        0..=15 => ANSI_COLORS[n as usize].to_string(),
+        16..=231 => {
+            let n = n - 16;
+            let level = |v: u16| if v == 0 { 0 } else { 55 + v * 40 };
+            format!("#{:02x}{:02x}{:02x}", level(n / 36), level((n / 6) % 6), level(n % 6))
+        }
+        _ => {
+            let gray = 8 + (n.min(255) - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[derive(Default)]
+struct Style {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    fn apply_sgr(&mut self, params: &str) {
+        let codes: Vec<u16> = params
+            .split(';')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let mut i = 0;
+
+        while i < codes.len() {
+            match codes[i] {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => self.bold = false,
+                23 => self.italic = false,
+                24 => self.underline = false,
+                c @ 30..=37 => self.fg = Some(ANSI_COLORS[(c - 30) as usize].to_string()),
+                c @ 90..=97 => self.fg = Some(ANSI_COLORS[(c - 90 + 8) as usize].to_string()),
+                c @ 40..=47 => self.bg = Some(ANSI_COLORS[(c - 40) as usize].to_string()),
+                c @ 100..=107 => self.bg = Some(ANSI_COLORS[(c - 100 + 8) as usize].to_string()),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                c @ (38 | 48) => {
+                    let color = match codes.get(i + 1) {
+                        Some(5) => {
+                            let color = codes.get(i + 2).map(|&n| color_256(n));
+                            i += 2;
+                            color
+                        }
+                        Some(2) => {
+                            let rgb = codes.get(i + 2..i + 5);
+                            i += 4;
+                            rgb.map(|c| format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2]))
+                        }
+                        _ => None,
+                    };
+                    if c == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some(fg) = &self.fg {
+            css.push(format!("color:{}", fg));
+        }
+        if let Some(bg) = &self.bg {
+            css.push(format!("background:{}", bg));
+        }
+        if self.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if self.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        css.join(";")
+    }
+}
+
+fn push_styled(body: &mut String, style: &Style, chunk: &str) {
+    let chunk = strip_ansi(chunk);
+    if chunk.is_empty() {
+        return;
+    }
+    let css = style.css();
+    if css.is_empty() {
+        body.push_str(&html_escape(&chunk));
+    } else {
+        body.push_str(&format!("<span style=\"{}\">{}</span>", css, html_escape(&chunk)));
+    }
+}
+
+/// Renders terminal output as a standalone HTML page, keeping SGR styling.
+pub fn ansi_to_html(text: &str) -> String {
+    static SGR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[([0-9;]*)m").unwrap());
+
+    let mut body = String::new();
+    let mut style = Style::default();
+    let mut last = 0;
+
+    for caps in SGR_RE.captures_iter(text) {
+        let m = caps.get(0).unwrap();
+        push_styled(&mut body, &style, &text[last..m.start()]);
+        style.apply_sgr(&caps[1]);
+        last = m.end();
+    }
+    push_styled(&mut body, &style, &text[last..]);
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Terminal transcript</title>\n</head>\n\
+         <body style=\"background:#1e1e1e;color:#e5e5e5\">\n\
+         <pre style=\"font-family:ui-monospace,Menlo,monospace;font-size:13px\">{}</pre>\n</body>\n</html>\n",
+        body
+    )
+}
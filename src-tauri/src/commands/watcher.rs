@@ -7,6 +7,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+use super::error::AppError;
+use super::sparse::sparse_watch_dirs;
+
 #[derive(Clone, serde::Serialize)]
 pub struct GitChangeEvent {
     pub repo_path: String,
@@ -54,7 +57,7 @@ impl GitWatcher {
         &self,
         repo_path: String,
         worktree_paths: Vec<String>,
-    ) -> Result<(), String> {
+    ) -> Result<(), AppError> {
         let mut watchers = self.watchers.lock();
         watchers.remove(&repo_path);
 
@@ -254,7 +257,7 @@ impl GitWatcher {
         file_watchers.clear();
     }
 
-    pub fn watch_worktree_files(&self, worktree_path: String) -> Result<(), String> {
+    pub fn watch_worktree_files(&self, worktree_path: String) -> Result<(), AppError> {
         let mut file_watchers = self.file_watchers.lock();
         file_watchers.remove(&worktree_path);
 
@@ -263,7 +266,9 @@ impl GitWatcher {
         let worktree_pathbuf = PathBuf::from(&worktree_path);
 
         if !worktree_pathbuf.exists() {
-            return Err("Worktree path does not exist".to_string());
+            return Err(AppError::NotFound(
+                "Worktree path does not exist".to_string(),
+            ));
         }
 
         let last_emit = Arc::new(Mutex::new(std::time::Instant::now()));
@@ -303,9 +308,24 @@ impl GitWatcher {
         .map_err(|e| e.to_string())?;
 
         let mut watcher = watcher;
-        watcher
-            .watch(&worktree_pathbuf, RecursiveMode::Recursive)
-            .map_err(|e| e.to_string())?;
+        match sparse_watch_dirs(&worktree_pathbuf) {
+            // Sparse worktrees only watch the checked-out cone
+            Some(dirs) => {
+                watcher
+                    .watch(&worktree_pathbuf, RecursiveMode::NonRecursive)
+                    .map_err(|e| e.to_string())?;
+                for dir in dirs.iter().filter(|d| d.exists()) {
+                    watcher
+                        .watch(dir, RecursiveMode::Recursive)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            None => {
+                watcher
+                    .watch(&worktree_pathbuf, RecursiveMode::Recursive)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
 
         file_watchers.insert(worktree_path, watcher);
         Ok(())
@@ -335,7 +355,7 @@ pub fn start_watching_repository(
     state: tauri::State<'_, WatcherState>,
     repo_path: String,
     worktree_paths: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut watcher_guard = state.watcher.lock();
 
     if watcher_guard.is_none() {
@@ -353,7 +373,7 @@ pub fn start_watching_repository(
 pub fn stop_watching_repository(
     state: tauri::State<'_, WatcherState>,
     repo_path: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let watcher_guard = state.watcher.lock();
 
     if let Some(ref watcher) = *watcher_guard {
@@ -364,7 +384,7 @@ pub fn stop_watching_repository(
 }
 
 #[tauri::command]
-pub fn stop_all_watchers(state: tauri::State<'_, WatcherState>) -> Result<(), String> {
+pub fn stop_all_watchers(state: tauri::State<'_, WatcherState>) -> Result<(), AppError> {
     let watcher_guard = state.watcher.lock();
 
     if let Some(ref watcher) = *watcher_guard {
@@ -379,7 +399,7 @@ pub fn start_watching_worktree_files(
     app_handle: AppHandle,
     state: tauri::State<'_, WatcherState>,
     worktree_path: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut watcher_guard = state.watcher.lock();
 
     if watcher_guard.is_none() {
@@ -397,7 +417,7 @@ pub fn start_watching_worktree_files(
 pub fn stop_watching_worktree_files(
     state: tauri::State<'_, WatcherState>,
     worktree_path: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let watcher_guard = state.watcher.lock();
 
     if let Some(ref watcher) = *watcher_guard {
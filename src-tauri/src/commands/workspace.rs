@@ -0,0 +1,402 @@
+use git2::{BranchType, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use super::error::AppError;
+use super::git::{self, open_repo, WorktreeInfo};
+use super::forge;
+use super::github::{CreatePRResult, PRStatus};
+use super::naming::{generate_unique_name, validate_branch_name, worktree_name_from_branch};
+use super::runner::Operation;
+use super::storage::{load_json, save_json};
+
+const WORKSPACES_FILE: &str = "workspaces.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkspaceRepo {
+    pub repo_path: String,
+    /// Branch new task worktrees start from; origin's main/master when unset
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Workspace {
+    pub name: String,
+    pub repos: Vec<WorkspaceRepo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct WorkspaceManifest {
+    workspaces: Vec<Workspace>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceTaskWorktree {
+    pub repo_path: String,
+    pub worktree: WorktreeInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceTask {
+    pub workspace: String,
+    pub branch_name: String,
+    pub worktrees: Vec<WorkspaceTaskWorktree>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceTaskPR {
+    pub repo_path: String,
+    pub pr: Option<PRStatus>,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceTaskPRResult {
+    pub repo_path: String,
+    pub result: Option<CreatePRResult>,
+    pub error: Option<AppError>,
+}
+
+fn load_manifest(app: &AppHandle) -> Result<WorkspaceManifest, AppError> {
+    load_json(app, WORKSPACES_FILE)
+}
+
+fn find_workspace(app: &AppHandle, name: &str) -> Result<Workspace, AppError> {
+    load_manifest(app)?
+        .workspaces
+        .into_iter()
+        .find(|w| w.name == name)
+        .ok_or_else(|| AppError::NotFound(format!("Workspace not found: {}", name)))
+}
+
+/// Returns the (name, path) of the worktree in `repo_path` that has `branch` checked out.
+fn find_worktree_for_branch(repo_path: &str, branch: &str) -> Option<(String, PathBuf)> {
+    let repo = Repository::open(repo_path).ok()?;
+    let worktrees = repo.worktrees().ok()?;
+
+    worktrees.iter().flatten().find_map(|name| {
+        let wt = repo.find_worktree(name).ok()?;
+        let wt_repo = Repository::open(wt.path()).ok()?;
+        let head = wt_repo.head().ok()?;
+        if head.is_branch() && head.shorthand() == Some(branch) {
+            Some((name.to_string(), wt.path().to_path_buf()))
+        } else {
+            None
+        }
+    })
+}
+
+#[tauri::command]
+pub fn list_workspaces(app: AppHandle) -> Result<Vec<Workspace>, AppError> {
+    Ok(load_manifest(&app)?.workspaces)
+}
+
+#[tauri::command]
+pub fn save_workspace(app: AppHandle, workspace: Workspace) -> Result<Workspace, AppError> {
+    if workspace.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Workspace name cannot be empty".to_string()));
+    }
+    if workspace.repos.is_empty() {
+        return Err(AppError::InvalidInput("Workspace needs at least one repository".to_string()));
+    }
+    for repo in &workspace.repos {
+        Repository::open(&repo.repo_path)
+            .map_err(|e| format!("{}: {}", repo.repo_path, e.message()))?;
+    }
+
+    let mut manifest = load_manifest(&app)?;
+    match manifest.workspaces.iter_mut().find(|w| w.name == workspace.name) {
+        Some(existing) => *existing = workspace.clone(),
+        None => manifest.workspaces.push(workspace.clone()),
+    }
+    save_json(&app, WORKSPACES_FILE, &manifest)?;
+
+    Ok(workspace)
+}
+
+#[tauri::command]
+pub fn delete_workspace(app: AppHandle, name: String) -> Result<(), AppError> {
+    let mut manifest = load_manifest(&app)?;
+    manifest.workspaces.retain(|w| w.name != name);
+    save_json(&app, WORKSPACES_FILE, &manifest)
+}
+
+/// Creates a worktree on the same branch in every repository of a workspace.
+/// If any repository fails, the worktrees already created are removed again.
+#[tauri::command]
+pub async fn create_workspace_task(
+    app: AppHandle,
+    workspace: String,
+    branch_name: Option<String>,
+    ticket: Option<String>,
+) -> Result<WorkspaceTask, AppError> {
+    let ws = find_workspace(&app, &workspace)?;
+    tokio::task::spawn_blocking(move || create_task_worktrees(&app, ws, branch_name, ticket))
+        .await?
+}
+
+fn create_task_worktrees(
+    app: &AppHandle,
+    ws: Workspace,
+    branch_name: Option<String>,
+    ticket: Option<String>,
+) -> Result<WorkspaceTask, AppError> {
+    let first_repo = ws.repos.first().ok_or("Workspace has no repositories")?;
+
+    let (branch_name, worktree_name) = match branch_name {
+        Some(branch) => {
+            validate_branch_name(&branch)?;
+            let worktree_name = worktree_name_from_branch(&branch);
+            (branch, worktree_name)
+        }
+        None => {
+            let repo = open_repo(&first_repo.repo_path)?;
+            let generated = generate_unique_name(&repo, ticket.as_deref())?;
+            (generated.branch_name, generated.worktree_name)
+        }
+    };
+
+    // Every expected collision is caught up front, so a failure below is never
+    // about something that already existed and rolling back can't remove it
+    let mut wt_paths = Vec::new();
+    for ws_repo in &ws.repos {
+        let repo = open_repo(&ws_repo.repo_path)?;
+        if repo.find_branch(&branch_name, BranchType::Local).is_ok() {
+            return Err(AppError::InvalidInput(format!(
+                "Branch {} already exists in {}",
+                branch_name, ws_repo.repo_path
+            )));
+        }
+        let wt_path = git::resolve_worktree_path(&repo, &ws_repo.repo_path, &worktree_name, None)?;
+        if repo.find_worktree(&worktree_name).is_ok() || wt_path.exists() {
+            return Err(AppError::InvalidInput(format!(
+                "Worktree {} already exists in {}",
+                worktree_name, ws_repo.repo_path
+            )));
+        }
+        wt_paths.push(wt_path);
+    }
+
+    let mut created: Vec<WorkspaceTaskWorktree> = Vec::new();
+    let mut failure: Option<String> = None;
+
+    for (ws_repo, wt_path) in ws.repos.iter().zip(&wt_paths) {
+        let repo_path = ws_repo.repo_path.clone();
+        let result = match &ws_repo.base_branch {
+            Some(base) => Ok(base.clone()),
+            None => open_repo(&repo_path)
+                .and_then(|repo| git::detect_remote_base_branch(&repo).map(String::from)),
+        }
+        .and_then(|base_branch| {
+            git::create_worktree_blocking(
+                app,
+                repo_path.clone(),
+                worktree_name.clone(),
+                base_branch,
+                Some(branch_name.clone()),
+                None,
+                None,
+                None,
+                None,
+            )
+        });
+
+        match result {
+            Ok(worktree) => created.push(WorkspaceTaskWorktree {
+                repo_path,
+                worktree,
+            }),
+            Err(e) => {
+                // Whatever the failing repo got before the error is ours too
+                if let Ok(repo) = Repository::open(&repo_path) {
+                    git::discard_new_worktree(&repo, &worktree_name, wt_path, Some(&branch_name));
+                }
+                failure = Some(format!("{}: {}", repo_path, e));
+                break;
+            }
+        }
+    }
+
+    if let Some(error) = failure {
+        for done in created {
+            let _ = git::delete_worktree_blocking(
+                app,
+                done.repo_path.clone(),
+                done.worktree.name.clone(),
+                true,
+            );
+            if let Ok(repo) = Repository::open(&done.repo_path) {
+                if let Ok(mut branch) = repo.find_branch(&branch_name, BranchType::Local) {
+                    let _ = branch.delete();
+                }
+            }
+        }
+        return Err(AppError::Other(error));
+    }
+
+    Ok(WorkspaceTask {
+        workspace: ws.name,
+        branch_name,
+        worktrees: created,
+    })
+}
+
+#[tauri::command]
+pub async fn get_workspace_task(
+    app: AppHandle,
+    workspace: String,
+    branch_name: String,
+) -> Result<WorkspaceTask, AppError> {
+    let ws = find_workspace(&app, &workspace)?;
+    let branch = branch_name.clone();
+
+    let worktrees = tokio::task::spawn_blocking(move || {
+        ws.repos
+            .iter()
+            .filter_map(|ws_repo| {
+                let (_, path) = find_worktree_for_branch(&ws_repo.repo_path, &branch)?;
+                let worktree = git::get_worktree_info(path.to_string_lossy().to_string()).ok()?;
+                Some(WorkspaceTaskWorktree {
+                    repo_path: ws_repo.repo_path.clone(),
+                    worktree,
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    Ok(WorkspaceTask {
+        workspace,
+        branch_name,
+        worktrees,
+    })
+}
+
+#[tauri::command]
+pub async fn get_workspace_task_prs(
+    app: AppHandle,
+    workspace: String,
+    branch_name: String,
+) -> Result<Vec<WorkspaceTaskPR>, AppError> {
+    let ws = find_workspace(&app, &workspace)?;
+    let mut results = Vec::new();
+
+    for ws_repo in ws.repos {
+        let result = forge::get_pr_for_branch(app.clone(), ws_repo.repo_path.clone(), branch_name.clone()).await;
+        let (pr, error) = match result {
+            Ok(pr) => (pr, None),
+            Err(e) => (None, Some(e)),
+        };
+        results.push(WorkspaceTaskPR {
+            repo_path: ws_repo.repo_path,
+            pr,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Pushes the task branch and opens a PR in every repository of the workspace.
+/// Failures are reported per repository so one bad remote doesn't block the rest.
+/// The pushes and PRs not yet requested can be cancelled through `operation_id`.
+#[tauri::command]
+pub async fn create_workspace_task_prs(
+    app: AppHandle,
+    workspace: String,
+    branch_name: String,
+    title: String,
+    body: Option<String>,
+    draft: bool,
+    operation_id: Option<String>,
+) -> Result<Vec<WorkspaceTaskPRResult>, AppError> {
+    let _operation = Operation::begin(operation_id.as_deref());
+    let ws = find_workspace(&app, &workspace)?;
+    let mut results = Vec::new();
+
+    for ws_repo in ws.repos {
+        let repo_path = ws_repo.repo_path.clone();
+        let branch = branch_name.clone();
+        let base_branch = ws_repo.base_branch.clone();
+        let push_operation = operation_id.clone();
+        let pushed = tokio::task::spawn_blocking(move || {
+            let (_, wt_path) = find_worktree_for_branch(&repo_path, &branch)
+                .ok_or_else(|| AppError::NotFound(format!("No worktree for branch {}", branch)))?;
+            let wt_path = wt_path.to_string_lossy().to_string();
+            git::run_git_long(
+                &wt_path,
+                &["push", "-u", "origin", &branch],
+                push_operation.as_deref(),
+            )?;
+
+            let base = base_branch.or_else(|| {
+                open_repo(&repo_path)
+                    .ok()
+                    .and_then(|repo| git::detect_remote_base_branch(&repo).ok().map(String::from))
+            });
+            Ok::<(String, Option<String>), AppError>((wt_path, base))
+        })
+        .await
+        .map_err(AppError::from)
+        .and_then(|r| r);
+
+        let outcome = match pushed {
+            Ok((wt_path, base)) => {
+                forge::create_pr(
+                    app.clone(),
+                    wt_path,
+                    title.clone(),
+                    body.clone(),
+                    base,
+                    draft,
+                    operation_id.clone(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e)),
+        };
+        results.push(WorkspaceTaskPRResult {
+            repo_path: ws_repo.repo_path,
+            result,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn delete_workspace_task(
+    app: AppHandle,
+    workspace: String,
+    branch_name: String,
+    force: bool,
+) -> Result<(), AppError> {
+    let ws = find_workspace(&app, &workspace)?;
+
+    let errors = tokio::task::spawn_blocking(move || {
+        ws.repos
+            .iter()
+            .filter_map(|ws_repo| {
+                let (name, _) = find_worktree_for_branch(&ws_repo.repo_path, &branch_name)?;
+                git::delete_worktree_blocking(&app, ws_repo.repo_path.clone(), name, force)
+                    .err()
+                    .map(|e| format!("{}: {}", ws_repo.repo_path, e))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Other(errors.join("\n")))
+    }
+}
@@ -2,7 +2,7 @@
 
 mod commands;
 
-use commands::{git, github, process, terminal, watcher};
+use commands::{agent, agent_adapter, attention, clone, commit_message, forge, git, github, naming, pr_description, process, recording, registry, runner, shell_integration, sparse, terminal, watcher, workspace};
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -80,6 +80,7 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             #[cfg(target_os = "macos")]
             {
@@ -95,12 +96,22 @@ pub fn run() {
         })
         .manage(AppState::default())
         .manage(watcher::WatcherState::default())
+        .manage(agent::AgentTasks::default())
         .invoke_handler(tauri::generate_handler![
             git::discover_repository,
             git::list_worktrees,
             git::create_worktree,
             git::create_worktree_auto,
+            git::create_worktree_from_branch,
+            git::create_worktree_from_pr,
+            git::create_worktree_from_commit,
+            git::update_worktree_submodules,
             git::delete_worktree,
+            git::lock_worktree,
+            git::unlock_worktree,
+            git::prune_worktrees,
+            git::move_worktree,
+            git::repair_worktrees,
             git::list_branches,
             git::get_worktree_info,
             git::get_worktree_branch_name,
@@ -116,17 +127,56 @@ pub fn run() {
             git::git_push,
             git::git_stage_all,
             git::git_unstage_all,
-            git::generate_commit_message,
+            commit_message::generate_commit_message,
+            pr_description::generate_pr_description,
+            naming::generate_worktree_name,
+            naming::get_naming_config,
+            naming::set_naming_config,
+            clone::clone_repository,
+            recording::get_recording_settings,
+            recording::set_recording_settings,
+            recording::list_recordings,
+            recording::delete_recording,
+            recording::prune_recordings,
+            recording::read_recording,
+            registry::list_repositories,
+            registry::register_repository,
+            registry::update_repository,
+            registry::remove_repository,
+            registry::relocate_repository,
+            registry::prune_missing_repositories,
+            registry::scan_for_repositories,
+            registry::import_repositories,
+            sparse::list_sparse_profiles,
+            sparse::save_sparse_profile,
+            sparse::delete_sparse_profile,
+            sparse::get_sparse_checkout,
+            sparse::set_sparse_checkout,
+            sparse::disable_sparse_checkout,
             github::check_gh_cli,
             github::check_gh_auth,
-            github::get_pr_for_branch,
-            github::get_all_prs_for_repos,
-            github::get_pr_status,
-            github::get_repo_from_remote,
-            github::get_pr_checks,
-            github::get_pr_details,
-            github::create_pr,
+            github::get_github_settings,
+            github::set_github_settings,
+            forge::get_pr_for_branch,
+            forge::get_all_prs_for_repos,
+            forge::get_pr_status,
+            forge::get_repo_from_remote,
+            forge::get_pr_checks,
+            forge::get_pr_details,
+            forge::create_pr,
+            forge::check_forge_auth,
+            forge::get_forge_settings,
+            forge::set_forge_settings,
             github::run_cubic_review,
+            agent::start_agent_task,
+            agent::list_agent_tasks,
+            agent::stop_agent_task,
+            agent::clear_finished_agent_tasks,
+            agent_adapter::list_agent_adapters,
+            agent_adapter::set_agent_adapters,
+            attention::get_attention_settings,
+            attention::set_attention_settings,
+            runner::cancel_operation,
             process::get_worktree_process_status,
             process::get_all_worktrees_process_status,
             terminal::spawn_terminal,
@@ -134,11 +184,25 @@ pub fn run() {
             terminal::write_to_terminal,
             terminal::resize_terminal,
             terminal::close_terminal,
+            terminal::list_terminals,
+            terminal::set_terminal_title,
+            terminal::get_terminal_history,
+            terminal::search_terminal_output,
+            terminal::export_terminal_transcript,
+            shell_integration::get_shell_integration_script,
             watcher::start_watching_repository,
             watcher::stop_watching_repository,
             watcher::stop_all_watchers,
             watcher::start_watching_worktree_files,
             watcher::stop_watching_worktree_files,
+            workspace::list_workspaces,
+            workspace::save_workspace,
+            workspace::delete_workspace,
+            workspace::create_workspace_task,
+            workspace::get_workspace_task,
+            workspace::get_workspace_task_prs,
+            workspace::create_workspace_task_prs,
+            workspace::delete_workspace_task,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../store';
 import type { BranchInfo } from '../types';
 import { useTheme } from '../hooks/useTheme';
+import { errorMessage } from '../utils/errors';
 
 interface Props {
   repoPath: string;
@@ -40,7 +41,7 @@ export function NewWorktreeDialog({ repoPath, onClose }: Props) {
       await refreshWorktrees(repoPath);
       onClose();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -11,6 +11,7 @@ import {
 import { useTheme } from "../../hooks/useTheme";
 import { useAppStore } from "../../store";
 import type { PRChecksResult, PRDetailedInfo, PRStatus } from "../../types/github";
+import { errorMessage } from "../../utils/errors";
 
 interface ChecksTabProps {
   repoPath: string | null;
@@ -144,7 +145,7 @@ export function ChecksTab({
       }
     } catch (e) {
       if (!isPolling) {
-        setError(String(e));
+        setError(errorMessage(e));
         setChecksResult(null);
         setPrDetails(null);
       }
@@ -7,6 +7,7 @@ import rehypeRaw from "rehype-raw";
 import { useTheme } from "../../hooks/useTheme";
 import { useAppStore } from "../../store";
 import type { PRDetailedInfo, PRStatus, PRComment } from "../../types/github";
+import { errorMessage } from "../../utils/errors";
 
 const AVATAR_COLORS = [
   '#6366F1', '#8B5CF6', '#EC4899', '#F97316', '#14B8A6',
@@ -152,7 +153,7 @@ export function CommentsTab({ repoPath, prNumber, prStatus }: CommentsTabProps)
       }
     } catch (e) {
       if (!isPolling) {
-        setError(String(e));
+        setError(errorMessage(e));
         setPrDetails(null);
       }
     } finally {
@@ -16,7 +16,7 @@ import {
 } from "lucide-react";
 import { useTheme } from "../../hooks/useTheme";
 import { useAppStore } from "../../store";
-import type { GitStatus, GitStatusFile } from "../../types";
+import type { CommitMessage, GitStatus, GitStatusFile } from "../../types";
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -24,6 +24,7 @@ import {
   DropdownMenuTrigger,
 } from "../ui/dropdown-menu";
 import { cn } from "../../utils/cn";
+import { errorMessage } from "../../utils/errors";
 
 interface GitTabProps {
   worktreePath: string | null;
@@ -84,7 +85,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       const status = await invoke<GitStatus>("get_git_status", { worktreePath });
       setGitStatus(status);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
       setGitStatus(null);
     } finally {
       setIsLoading(false);
@@ -126,7 +127,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       await invoke("git_stage_files", { worktreePath, files });
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsStaging(false);
     }
@@ -139,7 +140,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       await invoke("git_unstage_files", { worktreePath, files });
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsStaging(false);
     }
@@ -152,7 +153,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       await invoke("git_stage_all", { worktreePath });
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsStaging(false);
     }
@@ -165,7 +166,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       await invoke("git_unstage_all", { worktreePath });
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsStaging(false);
     }
@@ -180,7 +181,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       setCommitMessage("");
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsCommitting(false);
     }
@@ -194,7 +195,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
       await invoke("git_push", { worktreePath });
       await fetchStatus();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsPushing(false);
     }
@@ -205,13 +206,16 @@ export function GitTab({ worktreePath }: GitTabProps) {
     setIsGenerating(true);
     setError(null);
     try {
-      const message = await invoke<string>("generate_commit_message", {
+      const generated = await invoke<CommitMessage>("generate_commit_message", {
         worktreePath,
         agent: defaultAIAgent,
       });
-      setCommitMessage(message);
+      setCommitMessage(generated.message);
+      if (generated.warnings.length > 0) {
+        setError(generated.warnings.join("\n"));
+      }
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setIsGenerating(false);
     }
@@ -495,7 +499,7 @@ export function GitTab({ worktreePath }: GitTabProps) {
                     await fetchStatus();
                     await handleCommit();
                   } catch (e) {
-                    setError(String(e));
+                    setError(errorMessage(e));
                   }
                 }}
                 disabled={totalChanges === 0 || !commitMessage.trim()}
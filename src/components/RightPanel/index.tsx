@@ -16,7 +16,7 @@ import {
 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { useTheme } from "../../hooks/useTheme";
-import { usePRStatusForBranch } from "../../hooks/usePRStatus";
+import { usePRLabel, usePRStatusForBranch } from "../../hooks/usePRStatus";
 import { useAppStore } from "../../store";
 
 import { ChecksTab } from "./ChecksTab";
@@ -31,7 +31,7 @@ import {
   DropdownMenuItem,
   DropdownMenuTrigger,
 } from "../ui/dropdown-menu";
-import type { CreatePRResult } from "../../types/github";
+import type { CreatePRResult, PRDescription } from "../../types/github";
 
 interface RightPanelProps {
   worktreePath: string | null;
@@ -61,6 +61,7 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
   const selectedWorktree = useAppStore((state) => state.selectedWorktree);
   const repositories = useAppStore((state) => state.repositories);
   const addTerminalWithCommand = useAppStore((state) => state.addTerminalWithCommand);
+  const defaultAIAgent = useAppStore((state) => state.defaultAIAgent);
 
   const repoPath =
     repositories.find((r) => r.worktrees.some((w) => w.path === worktreePath))
@@ -68,6 +69,7 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
 
   const branch = selectedWorktree?.branch ?? null;
   const prStatus = usePRStatusForBranch(repoPath ?? "", branch);
+  const prLabel = usePRLabel(repoPath);
 
   const handleMouseDown = useCallback((e: React.MouseEvent) => {
     e.preventDefault();
@@ -106,10 +108,25 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
     setShowPRDropdown(false);
 
     try {
+      let title = branch || "New PR";
+      let body: string | null = null;
+      if (worktreePath) {
+        try {
+          const description = await invoke<PRDescription>("generate_pr_description", {
+            worktreePath,
+            agent: defaultAIAgent,
+            base: null,
+          });
+          title = description.title;
+          body = description.body;
+        } catch (e) {
+          console.error("Failed to generate PR description:", e);
+        }
+      }
       await invoke<CreatePRResult>("create_pr", {
         repoPath,
-        title: branch || "New PR",
-        body: null,
+        title,
+        body,
         base: null,
         draft,
       });
@@ -456,7 +473,7 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
               ) : (
                 <>
                   <GitPullRequest className="w-3.5 h-3.5" />
-                  Create PR
+                  Create {prLabel}
                   <ChevronDown className="w-3.5 h-3.5" />
                 </>
               )}
@@ -494,7 +511,7 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
                       e.currentTarget.style.background = "transparent";
                     }}
                   >
-                    Create PR
+                    Create {prLabel}
                   </button>
                   <button
                     onClick={() => handleCreatePR(true)}
@@ -507,7 +524,7 @@ export function RightPanel({ worktreePath }: RightPanelProps) {
                       e.currentTarget.style.background = "transparent";
                     }}
                   >
-                    Create draft PR
+                    Create draft {prLabel}
                   </button>
                 </motion.div>
               </>
@@ -16,6 +16,7 @@ import {
 import { useTheme } from "../hooks/useTheme";
 import { useAppStore } from "../store";
 import { AI_AGENTS, type AIAgent } from "../types";
+import type { ForgeAuth, ForgeSettings } from "../types/github";
 
 
 interface SettingsPanelProps {
@@ -40,7 +41,14 @@ interface NavItem {
 
 export function SettingsPanel({ onClose }: SettingsPanelProps) {
   const theme = useTheme();
-  const { githubSettings, defaultAIAgent, setDefaultAIAgent } = useAppStore();
+  const {
+    githubSettings,
+    forgeAuthByRepo,
+    forgeSettings,
+    setForgeSettings,
+    defaultAIAgent,
+    setDefaultAIAgent,
+  } = useAppStore();
   const [activeSection, setActiveSection] = useState<NavSection>("account");
 
   const navItems: NavItem[] = [
@@ -166,7 +174,17 @@ export function SettingsPanel({ onClose }: SettingsPanelProps) {
           </div>
 
           <div className="flex-1 overflow-y-auto px-6 py-5">
-            {activeSection === "account" && <AccountSection theme={theme} githubSettings={githubSettings} />}
+            {activeSection === "account" && (
+              <>
+                <AccountSection theme={theme} githubSettings={githubSettings} />
+                <GitLabSection
+                  theme={theme}
+                  forgeAuthByRepo={forgeAuthByRepo}
+                  forgeSettings={forgeSettings}
+                  setForgeSettings={setForgeSettings}
+                />
+              </>
+            )}
             {activeSection === "appearance" && <PlaceholderSection theme={theme} title="Appearance" description="Customize the look and feel of the application." />}
             {activeSection === "preferences" && (
               <PreferencesSection
@@ -310,6 +328,100 @@ function AccountSection({
   );
 }
 
+function GitLabSection({
+  theme,
+  forgeAuthByRepo,
+  forgeSettings,
+  setForgeSettings,
+}: {
+  theme: ReturnType<typeof useTheme>;
+  forgeAuthByRepo: Record<string, ForgeAuth>;
+  forgeSettings: ForgeSettings;
+  setForgeSettings: (settings: ForgeSettings) => Promise<void>;
+}) {
+  const [hosts, setHosts] = useState(forgeSettings.gitlab_hosts.join(", "));
+
+  // One entry per GitLab host, whichever repository reported it
+  const accounts = new Map<string, string | null>();
+  for (const auth of Object.values(forgeAuthByRepo)) {
+    if (auth.forge === "gitlab" && !accounts.get(auth.host)) {
+      accounts.set(auth.host, auth.user);
+    }
+  }
+
+  const saveHosts = () => {
+    const gitlab_hosts = hosts
+      .split(",")
+      .map((h) => h.trim().toLowerCase())
+      .filter((h) => h.length > 0);
+    if (gitlab_hosts.join(",") === forgeSettings.gitlab_hosts.join(",")) return;
+    setForgeSettings({ gitlab_hosts }).catch((e) => {
+      console.error("Failed to save forge settings:", e);
+    });
+  };
+
+  return (
+    <div className="space-y-4 mt-6">
+      <h4
+        className="text-xs font-medium uppercase"
+        style={{ color: theme.text.tertiary }}
+      >
+        GitLab Accounts
+      </h4>
+
+      {[...accounts].map(([host, user]) => (
+        <div
+          key={host}
+          className="p-3 rounded-lg border flex items-center gap-2"
+          style={{
+            background: theme.bg.primary,
+            borderColor: theme.border.default,
+          }}
+        >
+          {user ? (
+            <Check className="w-3.5 h-3.5" style={{ color: theme.semantic.success }} />
+          ) : (
+            <AlertCircle className="w-3.5 h-3.5" style={{ color: theme.semantic.error }} />
+          )}
+          <span className="text-sm font-medium" style={{ color: theme.text.primary }}>
+            {host}
+          </span>
+          <span className="text-xs" style={{ color: theme.text.tertiary }}>
+            {user ? `Authenticated as @${user}` : "No token - set GITLAB_TOKEN or run: glab auth login"}
+          </span>
+        </div>
+      ))}
+
+      <div>
+        <label
+          className="block text-sm font-medium mb-2"
+          style={{ color: theme.text.primary }}
+        >
+          Self-hosted GitLab hosts
+        </label>
+        <p className="text-xs mb-3" style={{ color: theme.text.tertiary }}>
+          Comma-separated hosts to treat as GitLab when their name doesn't contain "gitlab".
+        </p>
+        <input
+          value={hosts}
+          onChange={(e) => setHosts(e.target.value)}
+          onBlur={saveHosts}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") saveHosts();
+          }}
+          placeholder="git.example.com"
+          className="w-full px-3 py-2 rounded-lg text-sm outline-none"
+          style={{
+            background: theme.bg.primary,
+            border: `1px solid ${theme.border.default}`,
+            color: theme.text.primary,
+          }}
+        />
+      </div>
+    </div>
+  );
+}
+
 function DebugSection({
   theme,
   githubSettings,
@@ -11,12 +11,14 @@ import {
   ChevronRight,
   Archive,
   User,
+  AlertCircle,
 } from "lucide-react";
 import { useAppStore } from "../store";
 import type { WorktreeInfo } from "../types";
 import { NewWorktreeDialog } from "./NewWorktreeDialog";
 import { WorktreeItem } from "./WorktreeItem";
 import { useTheme, useThemeMode } from "../hooks/useTheme";
+import { errorMessage } from "../utils/errors";
 
 const MIN_WIDTH = 200;
 const MAX_WIDTH = 480;
@@ -46,6 +48,7 @@ export function Sidebar({ isOpen }: SidebarProps) {
     setThemeMode,
     toggleSettings,
     githubSettings,
+    prErrorByRepo,
   } = useAppStore();
   const theme = useTheme();
   const themeMode = useThemeMode();
@@ -111,7 +114,7 @@ export function Sidebar({ isOpen }: SidebarProps) {
       }
     } catch (e) {
       console.error("Failed to add repository:", e);
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -128,7 +131,7 @@ export function Sidebar({ isOpen }: SidebarProps) {
       }
     } catch (e) {
       console.error("Failed to create worktree:", e);
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -143,7 +146,7 @@ export function Sidebar({ isOpen }: SidebarProps) {
       await deleteWorktree(repoPath, worktreeName);
     } catch (e) {
       console.error("Failed to delete worktree:", e);
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
@@ -220,6 +223,14 @@ export function Sidebar({ isOpen }: SidebarProps) {
                      >
                        {group.repoName}
                      </span>
+                     {prErrorByRepo[group.repoPath] && (
+                       <span title={`Couldn't load pull requests: ${prErrorByRepo[group.repoPath].message}`}>
+                         <AlertCircle
+                           className="h-3 w-3 flex-shrink-0"
+                           style={{ color: theme.semantic.error }}
+                         />
+                       </span>
+                     )}
                      <span className="opacity-0 group-hover:opacity-100 transition-opacity">
                        {isCollapsed ? (
                          <ChevronRight
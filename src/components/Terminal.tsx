@@ -133,11 +133,16 @@ export function Terminal({ terminalId, isActive, isVisible, onFocus }: Props) {
       }
     );
 
-    const unlistenClose = listen<string>("terminal-closed", (event) => {
-      if (event.payload === terminalId) {
-        term.write("\r\n\x1b[31m[Process exited]\x1b[0m\r\n");
+    const unlistenClose = listen<{ terminal_id: string; exit_code: number | null }>(
+      "terminal-closed",
+      (event) => {
+        if (event.payload.terminal_id === terminalId) {
+          const code = event.payload.exit_code;
+          const suffix = code === null ? "" : ` with code ${code}`;
+          term.write(`\r\n\x1b[31m[Process exited${suffix}]\x1b[0m\r\n`);
+        }
       }
-    });
+    );
 
     resizeObserverRef.current = new ResizeObserver(() => {
       if (isVisible) {
@@ -1,6 +1,6 @@
 import { GitBranch, Trash2 } from "lucide-react";
 import { useTheme } from "../hooks/useTheme";
-import { usePRStatusForBranch } from "../hooks/usePRStatus";
+import { usePRLabel, usePRStatusForBranch } from "../hooks/usePRStatus";
 import { useAppStore } from "../store";
 import type { WorktreeInfo, ProcessStatus } from "../types";
 import type { PRStatus } from "../types/github";
@@ -93,6 +93,7 @@ export function WorktreeItem({
   const hasStats = wt.diff_stats && (wt.diff_stats.additions > 0 || wt.diff_stats.deletions > 0);
 
   const prStatus = usePRStatusForBranch(repoPath, wt.branch);
+  const prLabel = usePRLabel(repoPath);
   const statusInfo = getStatusInfo(prStatus, theme);
   const processStatus = useAppStore((state) => state.processStatusByPath[wt.path] || 'none');
   const processStatusColor = getProcessStatusColor(processStatus, theme);
@@ -182,7 +183,7 @@ export function WorktreeItem({
             <>
               <span style={{ color: statusInfo?.color }}>{statusInfo?.label}</span>
               <span className="font-mono text-xs font-bold">·</span>
-              <span>PR #{prStatus.number}</span>
+              <span>{prLabel} #{prStatus.number}</span>
               <span className="font-mono text-xs font-bold">·</span>
             </>
           )}
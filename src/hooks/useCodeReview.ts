@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { load } from "@tauri-apps/plugin-store";
 import type { ChangedFile, FileDiffData } from "../types";
+import { errorMessage } from "../utils/errors";
 
 interface GitIndexChangeEvent {
   repo_path: string;
@@ -57,7 +58,7 @@ export function useCodeReview(
       });
       setChangedFiles(files);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
       setChangedFiles([]);
     } finally {
       setIsLoading(false);
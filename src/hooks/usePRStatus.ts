@@ -1,27 +1,37 @@
-import { useEffect, useRef, useCallback } from 'react';
+import { useEffect, useRef, useCallback, useMemo } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../store';
+import type { AppError } from '../types';
 import type { PRStatus, RepoPRStatuses, RepoWithBranches } from '../types/github';
+import { PR_LABELS } from '../types/github';
 
 export function usePRStatusPolling() {
   const {
     repositories,
     githubSettings,
+    forgeAuthByRepo,
     setPRStatusBatch,
   } = useAppStore();
+
+  // Only repositories whose forge (GitHub or GitLab) has a signed-in user
+  const pollableRepos = useMemo(
+    () => repositories.filter((r) => forgeAuthByRepo[r.info.path]?.user),
+    [repositories, forgeAuthByRepo],
+  );
+  const canPoll = pollableRepos.length > 0;
   
   const pollingRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const isFetchingRef = useRef(false);
 
   const fetchAllPRs = useCallback(async () => {
-    if (!githubSettings.ghCliAvailable || isFetchingRef.current || repositories.length === 0) {
+    if (!canPoll || isFetchingRef.current) {
       return;
     }
 
     isFetchingRef.current = true;
 
     try {
-      const repos: RepoWithBranches[] = repositories.map(r => ({
+      const repos: RepoWithBranches[] = pollableRepos.map(r => ({
         repo_path: r.info.path,
         branches: r.worktrees
           .map(wt => wt.branch)
@@ -30,8 +40,16 @@ export function usePRStatusPolling() {
       
       const results = await invoke<RepoPRStatuses[]>('get_all_prs_for_repos', { repos });
       
+      const previous = useAppStore.getState().prStatusByBranch;
       const batch: Record<string, Record<string, PRStatus>> = {};
+      const errors: Record<string, AppError> = {};
       for (const result of results) {
+        if (result.error) {
+          // Keep showing the last known statuses until the repository can be reached again
+          errors[result.repo_path] = result.error;
+          batch[result.repo_path] = previous[result.repo_path] ?? {};
+          continue;
+        }
         const prMap: Record<string, PRStatus> = {};
         for (const pr of result.statuses) {
           prMap[pr.head_branch] = pr;
@@ -39,16 +57,16 @@ export function usePRStatusPolling() {
         batch[result.repo_path] = prMap;
       }
       
-      setPRStatusBatch(batch);
+      setPRStatusBatch(batch, errors);
     } catch (e) {
       console.error('Failed to fetch PRs:', e);
     } finally {
       isFetchingRef.current = false;
     }
-  }, [repositories, githubSettings.ghCliAvailable, setPRStatusBatch]);
+  }, [pollableRepos, canPoll, setPRStatusBatch]);
 
   useEffect(() => {
-    if (!githubSettings.ghCliAvailable) {
+    if (!canPoll) {
       return;
     }
 
@@ -65,7 +83,7 @@ export function usePRStatusPolling() {
         clearInterval(pollingRef.current);
       }
     };
-  }, [fetchAllPRs, githubSettings.pollingIntervalMs, githubSettings.ghCliAvailable]);
+  }, [fetchAllPRs, githubSettings.pollingIntervalMs, canPoll]);
 
   return { refresh: fetchAllPRs };
 }
@@ -80,3 +98,9 @@ export function usePRStatusForBranch(repoPath: string, branch: string | null): P
   
   return repoStatuses[branch] || null;
 }
+
+/** "MR" for repositories on GitLab, "PR" everywhere else. */
+export function usePRLabel(repoPath: string | null): string {
+  const forge = useAppStore((state) => (repoPath ? state.forgeAuthByRepo[repoPath]?.forge : undefined));
+  return PR_LABELS[forge ?? 'github'];
+}
@@ -1,8 +1,8 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { load } from '@tauri-apps/plugin-store';
-import type { Repository, WorktreeInfo, TerminalInstance, ProcessStatus, DiffViewMode, AIAgent } from '../types';
-import type { GitHubSettings, PRStatus, PRChecksResult, PRDetailedInfo } from '../types/github';
+import type { Repository, WorktreeInfo, TerminalInstance, ProcessStatus, DiffViewMode, AIAgent, AppError } from '../types';
+import type { GitHubSettings, PRStatus, PRChecksResult, PRDetailedInfo, ForgeAuth, ForgeSettings } from '../types/github';
 import { DEFAULT_GITHUB_SETTINGS } from '../types/github';
 import { setThemeMode as setGlobalThemeMode, getThemeMode, type ThemeMode } from '../theme';
 
@@ -30,7 +30,10 @@ interface AppStore {
   currentActiveTerminalId: string | null;
   isInitialized: boolean;
   githubSettings: GitHubSettings;
+  forgeAuthByRepo: Record<string, ForgeAuth>;
+  forgeSettings: ForgeSettings;
   prStatusByBranch: Record<string, Record<string, PRStatus>>;
+  prErrorByRepo: Record<string, AppError>;
   prDataCache: Record<string, PRDataCache>;
   collapsedRepos: Set<string>;
   settingsOpen: boolean;
@@ -62,12 +65,14 @@ interface AppStore {
   toggleDiffViewMode: () => void;
   createWorktreeAuto: (repoPath: string) => Promise<WorktreeInfo | null>;
   deleteWorktree: (repoPath: string, worktreeName: string) => Promise<void>;
-  setPRStatusBatch: (batch: Record<string, Record<string, PRStatus>>) => void;
+  setPRStatusBatch: (batch: Record<string, Record<string, PRStatus>>, errors?: Record<string, AppError>) => void;
   setPRDataCache: (repoPath: string, prNumber: number, data: { checksResult?: PRChecksResult | null; prDetails?: PRDetailedInfo | null }) => void;
   getPRDataCache: (repoPath: string, prNumber: number) => PRDataCache | null;
   clearPRDataCacheForRepo: (repoPath: string) => void;
   setPollingInterval: (intervalMs: number) => void;
   checkGitHubCli: () => Promise<void>;
+  checkForgeAuth: (repoPath: string) => Promise<void>;
+  setForgeSettings: (settings: ForgeSettings) => Promise<void>;
   refreshProcessStatuses: () => Promise<void>;
   getProcessStatus: (worktreePath: string) => ProcessStatus;
   setDefaultAIAgent: (agent: AIAgent) => Promise<void>;
@@ -105,7 +110,10 @@ export const useAppStore = create<AppStore>((set, get) => ({
   currentActiveTerminalId: null,
   isInitialized: false,
   githubSettings: DEFAULT_GITHUB_SETTINGS,
+  forgeAuthByRepo: {},
+  forgeSettings: { gitlab_hosts: [] },
   prStatusByBranch: {},
+  prErrorByRepo: {},
   prDataCache: {},
   collapsedRepos: new Set<string>(),
   settingsOpen: false,
@@ -147,6 +155,15 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set({ isInitialized: true });
     
     get().checkGitHubCli();
+
+    try {
+      set({ forgeSettings: await invoke<ForgeSettings>('get_forge_settings') });
+    } catch (e) {
+      console.error('Failed to load forge settings:', e);
+    }
+    for (const repo of get().repositories) {
+      get().checkForgeAuth(repo.info.path);
+    }
   },
 
   addRepository: async (path: string) => {
@@ -164,6 +181,7 @@ export const useAppStore = create<AppStore>((set, get) => ({
         
         return { repositories: newRepos };
       });
+      get().checkForgeAuth(info.path);
     } catch (e) {
       console.error('Failed to add repository:', e);
       throw e;
@@ -174,7 +192,9 @@ export const useAppStore = create<AppStore>((set, get) => ({
     set((state) => {
       const newRepos = state.repositories.filter((r) => r.info.path !== path);
       savePersistedState({ repositoryPaths: newRepos.map((r) => r.info.path) });
-      return { repositories: newRepos };
+      const forgeAuthByRepo = { ...state.forgeAuthByRepo };
+      delete forgeAuthByRepo[path];
+      return { repositories: newRepos, forgeAuthByRepo };
     });
   },
 
@@ -446,8 +466,8 @@ export const useAppStore = create<AppStore>((set, get) => ({
     }
   },
 
-  setPRStatusBatch: (batch: Record<string, Record<string, PRStatus>>) => {
-    set({ prStatusByBranch: batch });
+  setPRStatusBatch: (batch: Record<string, Record<string, PRStatus>>, errors: Record<string, AppError> = {}) => {
+    set({ prStatusByBranch: batch, prErrorByRepo: errors });
   },
 
   setPRDataCache: (repoPath: string, prNumber: number, data: { checksResult?: PRChecksResult | null; prDetails?: PRDetailedInfo | null }) => {
@@ -496,12 +516,19 @@ export const useAppStore = create<AppStore>((set, get) => ({
 
   checkGitHubCli: async () => {
     try {
-      const available = await invoke<boolean>('check_gh_cli');
+      // Hosts come from each repository's remote, so GitHub Enterprise repos are checked too
+      const repoPaths: (string | null)[] = get().repositories.map((r) => r.info.path);
+      if (repoPaths.length === 0) repoPaths.push(null);
+
+      let available = false;
       let user: string | null = null;
-      
-      if (available) {
+
+      for (const repoPath of repoPaths) {
+        if (!(await invoke<boolean>('check_gh_cli', { repoPath }))) continue;
+        available = true;
         try {
-          user = await invoke<string>('check_gh_auth');
+          user = await invoke<string>('check_gh_auth', { repoPath });
+          break;
         } catch {
           user = null;
         }
@@ -526,6 +553,26 @@ export const useAppStore = create<AppStore>((set, get) => ({
     }
   },
 
+  checkForgeAuth: async (repoPath: string) => {
+    try {
+      const auth = await invoke<ForgeAuth>('check_forge_auth', { repoPath });
+      set((state) => ({
+        forgeAuthByRepo: { ...state.forgeAuthByRepo, [repoPath]: auth },
+      }));
+    } catch (e) {
+      // No parsable origin remote, so there's no forge to poll
+      console.error(`Failed to check forge for ${repoPath}:`, e);
+    }
+  },
+
+  setForgeSettings: async (settings: ForgeSettings) => {
+    await invoke('set_forge_settings', { settings });
+    set({ forgeSettings: settings });
+    for (const repo of get().repositories) {
+      get().checkForgeAuth(repo.info.path);
+    }
+  },
+
   refreshProcessStatuses: async () => {},
 
   getProcessStatus: (worktreePath: string): ProcessStatus => {
@@ -1,3 +1,5 @@
+import type { AppError } from './index';
+
 export type PRReviewDecision = 'APPROVED' | 'CHANGES_REQUESTED' | 'REVIEW_REQUIRED' | null;
 
 export type ChecksStatus = 'success' | 'failure' | 'pending' | null;
@@ -24,6 +26,7 @@ export interface RepoWithBranches {
 export interface RepoPRStatuses {
   repo_path: string;
   statuses: PRStatus[];
+  error: AppError | null;
 }
 
 export interface GitHubSettings {
@@ -38,6 +41,31 @@ export const DEFAULT_GITHUB_SETTINGS: GitHubSettings = {
   ghAuthUser: null,
 };
 
+// 'auto' uses the HTTP API when a token is found (env, gh config or keychain), the gh CLI otherwise
+export type GitHubBackendKind = 'auto' | 'cli' | 'native';
+
+export interface GitHubBackendSettings {
+  backend: GitHubBackendKind;
+}
+
+// Forge hosting a repository's origin; GitLab merge requests use the PR types above
+export type ForgeKind = 'github' | 'gitlab';
+
+export interface ForgeSettings {
+  gitlab_hosts: string[];
+}
+
+export interface ForgeAuth {
+  forge: ForgeKind;
+  host: string;
+  user: string | null;
+}
+
+export const PR_LABELS: Record<ForgeKind, string> = {
+  github: 'PR',
+  gitlab: 'MR',
+};
+
 export const POLLING_INTERVALS = {
   fast: 15000,
   normal: 30000,
@@ -63,6 +91,12 @@ export interface CreatePRResult {
   url: string;
 }
 
+export interface PRDescription {
+  title: string;
+  body: string;
+  used_template: boolean;
+}
+
 export interface CubicReviewResult {
   success: boolean;
   output: string;
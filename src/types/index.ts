@@ -3,12 +3,39 @@ export interface RepoInfo {
   name: string;
 }
 
+export interface RepoHooks {
+  post_create?: string;
+  pre_delete?: string;
+}
+
+export interface RepoSettings {
+  base_branch?: string;
+  hooks: RepoHooks;
+}
+
+export interface RegisteredRepo {
+  path: string;
+  name: string;
+  display_name?: string;
+  pinned: boolean;
+  last_opened?: string;
+  settings: RepoSettings;
+  missing: boolean;
+}
+
 export interface WorktreeInfo {
   name: string;
   path: string;
   branch: string | null;
   last_modified: string | null;
   diff_stats?: DiffStats;
+  dirty?: DirtyStats | null;
+  upstream?: AheadBehind | null;
+  base?: AheadBehind | null;
+  last_commit?: LastCommitInfo | null;
+  locked?: boolean;
+  lock_reason?: string | null;
+  prunable?: boolean;
 }
 
 export interface BranchInfo {
@@ -34,6 +61,24 @@ export interface DiffStats {
   deletions: number;
 }
 
+export interface DirtyStats {
+  staged: number;
+  unstaged: number;
+  untracked: number;
+}
+
+export interface AheadBehind {
+  reference: string;
+  ahead: number;
+  behind: number;
+}
+
+export interface LastCommitInfo {
+  id: string;
+  summary: string;
+  time: string;
+}
+
 export interface Repository {
   info: RepoInfo;
   worktrees: WorktreeInfo[];
@@ -64,9 +109,21 @@ export interface GitStatusFile {
   staged: boolean;
 }
 
+export type SubmoduleState = 'uninitialized' | 'modified_commit' | 'dirty' | 'clean';
+
+export interface SubmoduleStatusEntry {
+  name: string;
+  path: string;
+  state: SubmoduleState;
+  staged: boolean;
+  head_id: string | null;
+  workdir_id: string | null;
+}
+
 export interface GitStatus {
   staged: GitStatusFile[];
   unstaged: GitStatusFile[];
+  submodules: SubmoduleStatusEntry[];
   branch: string | null;
   upstream_branch: string | null;
   ahead: number;
@@ -82,3 +139,155 @@ export const AI_AGENTS: { id: AIAgent; name: string; command: string }[] = [
   { id: 'amp', name: 'Amp', command: 'amp' },
   { id: 'codex', name: 'Codex', command: 'codex' },
 ];
+
+export interface CloneProgress {
+  destination: string;
+  received_objects: number;
+  total_objects: number;
+  indexed_deltas: number;
+  total_deltas: number;
+  received_bytes: number;
+}
+
+export interface TerminalInfo {
+  terminal_id: string;
+  cwd: string;
+  worktree?: string;
+  title?: string;
+  command?: string;
+  pid?: number;
+  started_at: string;
+  alive: boolean;
+  exit_code?: number;
+  exited_at?: string;
+  last_activity: string;
+  command_exit_code?: number;
+  shell_integration: boolean;
+  needs_attention: boolean;
+}
+
+export interface TerminalCommandFinished {
+  terminal_id: string;
+  command?: string;
+  exit_code?: number;
+  duration_ms: number;
+}
+
+export interface CommandRecord {
+  command?: string;
+  cwd?: string;
+  started_at: string;
+  finished_at?: string;
+  exit_code?: number;
+  duration_ms?: number;
+}
+
+export type OutputEncoding = 'utf8' | 'base64';
+
+export interface TerminalOutput {
+  terminal_id: string;
+  data: string;
+  encoding: OutputEncoding;
+}
+
+export type TranscriptFormat = 'text' | 'html' | 'cast';
+
+export interface OutputMatch {
+  line_number: number;
+  line: string;
+  start: number;
+  end: number;
+  before: string[];
+  after: string[];
+}
+
+export interface RecordingSettings {
+  enabled: boolean;
+  retention_days: number;
+  max_per_worktree: number;
+}
+
+export interface RecordingInfo {
+  path: string;
+  started_at?: string;
+  title?: string;
+  width?: number;
+  height?: number;
+  size_bytes: number;
+}
+
+export type AgentTaskStatus = 'running' | 'waiting_for_input' | 'finished' | 'failed';
+
+export interface AgentTask {
+  task_id: string;
+  terminal_id: string;
+  worktree: string;
+  agent: string;
+  prompt: string;
+  status: AgentTaskStatus;
+  exit_code?: number;
+  started_at: string;
+  updated_at: string;
+  finished_at?: string;
+}
+
+export interface AttentionSettings {
+  idle_ms: number;
+  notify: boolean;
+  patterns: Record<string, string[]>;
+}
+
+export interface AgentNeedsAttention {
+  terminal_id: string;
+  worktree?: string;
+  agent?: string;
+  line: string;
+}
+
+export interface AgentConfig {
+  name: string;
+  command?: string;
+  args: string[];
+  allowed_tools_args: string[];
+  output_pattern?: string;
+  timeout_secs: number;
+}
+
+export interface CommitMessage {
+  subject: string;
+  body?: string;
+  message: string;
+  warnings: string[];
+}
+
+export type OutputStream = 'stdout' | 'stderr';
+
+export interface ProcessOutputLine {
+  operation_id: string;
+  stream: OutputStream;
+  line: string;
+}
+
+export type AppErrorCode =
+  | 'repo_not_found'
+  | 'base_branch_missing'
+  | 'gh_not_installed'
+  | 'gh_not_authenticated'
+  | 'merge_conflict'
+  | 'timeout'
+  | 'cancelled'
+  | 'tool_not_found'
+  | 'command_failed'
+  | 'api'
+  | 'invalid_input'
+  | 'not_found'
+  | 'git'
+  | 'io'
+  | 'other';
+
+// Rejection value of every invoke() call
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details?: Record<string, unknown> | null;
+}
@@ -0,0 +1,18 @@
+import type { AppError } from "../types";
+
+export function isAppError(e: unknown): e is AppError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    typeof (e as AppError).code === "string" &&
+    typeof (e as AppError).message === "string"
+  );
+}
+
+/** Human-readable message for anything an invoke() call rejects with. */
+export function errorMessage(e: unknown): string {
+  if (isAppError(e) || e instanceof Error) {
+    return e.message;
+  }
+  return String(e);
+}